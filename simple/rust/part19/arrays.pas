@@ -0,0 +1,54 @@
+program Arrays;
+
+const
+   N = 5;
+
+type
+   TColor = (Red, Green, Blue);
+   TVector = array[1..N] of integer;
+   TMatrix = array[0..2, 0..2] of real;
+
+var
+   v : TVector;
+   m : TMatrix;
+   counts : array[TColor] of integer;
+   flags : array[boolean] of boolean;
+   i, j, sum : integer;
+   trace : real;
+   c : TColor;
+
+procedure Swap(var a, b : integer);
+var t : integer;
+begin
+   t := a;
+   a := b;
+   b := t
+end;
+
+function Total(const x : TVector) : integer;
+var k, s : integer;
+begin
+   s := 0;
+   for k := 1 to N do
+      s := s + x[k];
+   Total := s
+end;
+
+begin
+   for i := 1 to N do
+      v[i] := i * i;
+   Swap(v[1], v[N]);
+   sum := Total(v);
+
+   for i := 0 to 2 do
+      for j := 0 to 2 do
+         if i = j then
+            m[i, j] := 1.5
+         else
+            m[i][j] := i - j;
+   trace := m[0, 0] + m[1][1] + m[2, 2];
+
+   for c := Red to Blue do
+      counts[c] := v[2] + 1;
+   flags[true] := v[1] > v[2]
+end.
@@ -0,0 +1,23 @@
+program CaseOf;
+
+var i, small, large, other, mixed : integer;
+    flag : boolean;
+
+begin
+   small := 0; large := 0; other := 0; mixed := 0;
+   for i := -2 to 12 do
+      case i of
+         1, 2: small := small + 1;
+         5..9: large := large + 1;
+         -2, 3..4: begin
+            mixed := mixed + 1
+         end
+      else
+         other := other + 1
+      end;
+
+   case small = 2 of
+      true: flag := true;
+      false: flag := false;
+   end
+end.
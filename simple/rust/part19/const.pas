@@ -0,0 +1,30 @@
+program Constants;
+
+const
+   MaxN = 10;
+   Half = MaxN div 2;
+   Pi2 = 2 * 3.14159;
+   Debug = false;
+   Limit = -Half + 1;
+
+var i, total : integer;
+    circle : real;
+    enabled : boolean;
+
+function Scaled(x : integer) : integer;
+const Factor = MaxN * 10;
+begin
+   Scaled := x * Factor
+end;
+
+begin
+   total := 0;
+   for i := Limit to MaxN do
+      total := total + i;
+   case total of
+      Half: total := 0;
+      MaxN + 1..100: total := Scaled(total)
+   end;
+   circle := Pi2 * MaxN;
+   enabled := Debug = false
+end.
@@ -0,0 +1,60 @@
+program Control;
+
+var
+   i, sum : integer;
+   words : string;
+
+{ exit with a value gives the result of the function }
+function FirstSpace(s : string) : integer;
+var
+   i : integer;
+begin
+   for i := 1 to length(s) do
+      if s[i] = ' ' then
+         exit(i);
+   FirstSpace := 0
+end;
+
+procedure Countdown(n : integer);
+begin
+   while true do
+   begin
+      if n = 0 then
+      begin
+         writeln('liftoff');
+         exit
+      end;
+      write(n, ' ');
+      n := n - 1
+   end
+end;
+
+begin
+   sum := 0;
+   for i := 1 to 100 do
+   begin
+      if i mod 2 = 0 then
+         continue;
+      if i > 10 then
+         break;
+      sum := sum + i
+   end;
+   writeln('odd numbers up to 10: ', sum, ', stopped at ', i);
+
+   i := 0;
+   repeat
+      i := i + 1;
+      if i = 3 then
+         continue;
+      write(i, ' ')
+   until i = 5;
+   writeln;
+
+   words := 'hello world';
+   writeln('first space at ', FirstSpace(words), ', none in ', FirstSpace('pascal'));
+   Countdown(3);
+
+   { halt stops the program with the given exit code }
+   halt(3);
+   writeln('never written')
+end.
@@ -0,0 +1,57 @@
+program Files;
+
+var
+   report, data : text;
+   line : string;
+   count, total, value : integer;
+
+procedure WriteHeader(var f : text; title : string);
+begin
+   writeln(f, '# ', title)
+end;
+
+begin
+   assign(report, 'report.txt');
+   rewrite(report);
+   WriteHeader(report, 'numbers');
+   writeln(report, 10, ' ', 20);
+   write(report, 30);
+   writeln(report);
+   close(report);
+
+   { more lines are added to the end of the file }
+   append(report);
+   writeln(report, 40:4, 50:4);
+   close(report);
+
+   reset(report);
+   readln(report, line);
+   writeln('header: ', line);
+   count := 0;
+   total := 0;
+   while not eof(report) do
+   begin
+      while not eoln(report) do
+      begin
+         read(report, value);
+         count := count + 1;
+         total := total + value
+      end;
+      readln(report)
+   end;
+   close(report);
+   writeln('numbers: ', count, ', total: ', total);
+
+   { with the I/O checks off errors are returned by IOResult }
+   assign(data, 'missing.txt');
+   {$I-}
+   reset(data);
+   {$I+}
+   if IOResult <> 0 then
+      writeln('missing.txt not found');
+   {$I-}
+   close(data);
+   {$I+}
+   writeln('closing a closed file: ', IOResult);
+   writeln('no error since: ', IOResult)
+end.
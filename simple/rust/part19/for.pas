@@ -0,0 +1,21 @@
+program ForLoops;
+
+var i, j, sum, countdown : integer;
+    flag : boolean;
+
+begin
+   sum := 0;
+   for i := 1 to 10 do
+      for j := i downto 1 do
+         sum := sum + j;
+
+   countdown := 0;
+   for i := 5 downto 1 do
+      countdown := countdown * 10 + i;
+
+   for flag := false to true do
+      sum := sum + 1;
+
+   for i := 10 to 1 do
+      sum := 0
+end.
@@ -0,0 +1,44 @@
+program Functions;
+
+var x, y, big : integer;
+    avg : real;
+    positive : boolean;
+
+function Max(a, b : integer) : integer;
+begin
+   if a > b then
+      Max := a
+   else
+      Max := b
+end;
+
+function Average(a, b : integer) : real;
+begin
+   Result := (a + b) / 2
+end;
+
+function Square(n : integer) : integer;
+var s : integer;
+begin
+   s := n * n;
+   Square := s
+end;
+
+function IsPositive(n : integer) : boolean;
+begin
+   IsPositive := n > 0
+end;
+
+procedure Nothing;
+begin
+end;
+
+begin
+   x := 3;
+   y := 8;
+   big := Max(x, y) * 2 + Square(x);
+   avg := Average(x, y);
+   positive := IsPositive(x - y);
+   Nothing;
+   Max(1, 2)
+end.
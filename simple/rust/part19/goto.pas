@@ -0,0 +1,46 @@
+program Jumps;
+
+label 10, done;
+
+var
+   i, j, found : integer;
+
+{ labels belong to the block they are declared in }
+function Gcd(a, b : integer) : integer;
+label 1;
+var
+   t : integer;
+begin
+1: if b <> 0 then
+   begin
+      t := a mod b;
+      a := b;
+      b := t;
+      goto 1
+   end;
+   Gcd := a
+end;
+
+begin
+   i := 0;
+10:
+   i := i + 1;
+   write(i, ' ');
+   if i < 5 then
+      goto 10;
+   writeln;
+
+   { goto leaves nested loops at once }
+   found := 0;
+   for i := 1 to 9 do
+      for j := 1 to 9 do
+         if i * j = 42 then
+         begin
+            found := i * 10 + j;
+            goto done
+         end;
+   writeln('never written');
+done:
+   writeln('first factors of 42: ', found div 10, ' and ', found mod 10);
+   writeln('gcd(84, 36) = ', Gcd(84, 36))
+end.
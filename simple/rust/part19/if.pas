@@ -0,0 +1,22 @@
+program IfElse;
+
+var a, b : integer;
+    found : boolean;
+    max : real;
+
+begin
+   a := 10;
+   b := 3;
+   found := a = 10;
+   if a < b then
+      max := b
+   else
+      max := a;
+   if (a >= 10) = True then
+      if b <> 3 then
+         b := 0
+      else
+         b := b * 2;
+   if found then
+      found := False
+end.
@@ -0,0 +1,20 @@
+program Loops;
+
+var n, i, fact, sum : integer;
+
+begin
+   n := 10;
+   i := 1;
+   fact := 1;
+   while i <= n do
+   begin
+      fact := fact * i;
+      i := i + 1
+   end;
+
+   sum := 0;
+   repeat
+      sum := sum + n;
+      n := n - 1
+   until n = 0
+end.
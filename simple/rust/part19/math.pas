@@ -0,0 +1,28 @@
+program Math;
+
+const
+   Side = 4;
+   Area = sqr(Side);
+   Half = round(2.5);
+
+var
+   i : integer;
+   x, pi : real;
+
+begin
+   pi := 4 * arctan(1);
+   writeln('pi = ', pi:0:6);
+   writeln('abs: ', abs(-7), ' ', abs(-2.5):0:1);
+   writeln('sqr: ', sqr(5), ' ', sqr(1.5):0:2, ' ', Area);
+   writeln('sqrt: ', sqrt(2):0:4);
+   writeln('sin/cos: ', sin(pi / 2):0:3, ' ', cos(pi):0:3);
+   writeln('exp/ln: ', exp(1):0:5, ' ', ln(exp(2)):0:1);
+   writeln('round: ', round(2.5), ' ', round(-2.5), ' ', round(2.4), ' ', Half);
+   writeln('trunc: ', trunc(2.9), ' ', trunc(-2.9));
+   for i := 1 to 4 do
+      if odd(i) then
+         writeln(i, ' is odd');
+   x := sqrt(16);
+   i := trunc(x) + round(x / 3);
+   writeln(x:0:1, ' ', i)
+end.
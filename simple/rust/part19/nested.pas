@@ -0,0 +1,42 @@
+program Nested;
+var total, x : integer;
+
+procedure Alpha(a : integer; b : integer);
+var x : integer;
+
+   procedure Beta(depth : integer);
+   begin
+      x := x + a * b;
+      total := total + 1;
+      if depth > 0 then Beta(depth - 1)
+   end;
+
+begin
+   x := 0;
+   Beta(2);
+   writeln('alpha x = ', x)
+end;
+
+function Sum(n : integer) : integer;
+var i, acc : integer;
+   procedure Add(k : integer);
+   begin
+      acc := acc + k
+   end;
+   procedure Finish;
+   begin
+      Sum := acc
+   end;
+begin
+   acc := 0;
+   for i := 1 to n do Add(i);
+   Finish
+end;
+
+begin
+   x := 100;
+   total := 0;
+   Alpha(3, 7);
+   writeln('main x = ', x, ', total = ', total);
+   writeln('sum = ', Sum(10))
+end.
@@ -0,0 +1,39 @@
+program Operators;
+
+const
+   Mask = 255 shl 8;
+   Flags = 5 or 2;
+
+var
+   i, n : integer;
+   found : boolean;
+   a : array[1..5] of integer;
+
+begin
+   writeln('mod: ', 17 mod 5, ' ', -17 mod 5, ' ', 17 mod -5);
+   writeln('shl/shr: ', 1 shl 10, ' ', 1024 shr 3, ' ', Mask, ' ', -1 shr 28);
+   writeln('bitwise: ', 12 and 10, ' ', 12 or 10, ' ', 12 xor 10, ' ', not 0, ' ', Flags);
+   writeln('boolean: ', true and false, ' ', true or false, ' ', true xor true, ' ', not false);
+   { not binds tighter than and, and tighter than or, relations are the loosest }
+   writeln('precedence: ', not false and false, ' ', true or true and false, ' ', 2 + 3 * 4 mod 5);
+   writeln((1 < 2) and (3 < 4), ' ', (1 > 2) or (3 > 4));
+   for i := 1 to 5 do
+      a[i] := i * i;
+   { the right operand is not evaluated once the left one decides the result }
+   n := 9;
+   i := 1;
+   while (i <= 5) and (a[i] <> n) do
+      i := i + 1;
+   found := (i <= 5) and (a[i] = n);
+   writeln('found ', n, ': ', found, ' at ', i);
+   n := 10;
+   i := 1;
+   while (i <= 5) and (a[i] <> n) do
+      i := i + 1;
+   found := (i <= 5) and (a[i] = n);
+   writeln('found ', n, ': ', found);
+   for i := 1 to 10 do
+      if (i mod 3 = 0) or (i mod 5 = 0) then
+         write(i, ' ');
+   writeln
+end.
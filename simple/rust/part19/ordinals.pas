@@ -0,0 +1,36 @@
+program Ordinals;
+
+type
+   Color = (Red, Green, Blue);
+   Digit = 0..9;
+
+const
+   Last = high(Color);
+   Letters = ord('z') - ord('a') + 1;
+
+var
+   c : Color;
+   d : Digit;
+   ch : char;
+   i : integer;
+   counts : array[Color] of integer;
+
+begin
+   writeln('ord: ', ord(Green), ' ', ord('A'), ' ', ord(true));
+   writeln('chr: ', chr(72), chr(105));
+   writeln('succ/pred: ', succ(Red), ' ', pred(Blue), ' ', succ('a'), ' ', pred(10));
+   writeln('low/high: ', low(Color), ' ', Last, ' ', low(Digit), ' ', high(d), ' ', high(boolean));
+   writeln('letters: ', Letters);
+   for c := low(counts) to high(counts) do
+      counts[c] := ord(c) * 10;
+   c := Red;
+   inc(c);
+   inc(counts[c], 5);
+   writeln(c, ' ', counts[c]);
+   i := 10;
+   dec(i, 3);
+   inc(i);
+   ch := 'a';
+   inc(ch, 2);
+   writeln(i, ' ', ch)
+end.
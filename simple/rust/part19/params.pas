@@ -0,0 +1,41 @@
+program Params;
+
+var a, b, lo, hi, sum : integer;
+
+procedure Swap(var x, y : integer);
+var t : integer;
+begin
+   t := x;
+   x := y;
+   y := t
+end;
+
+procedure Order(var x, y : integer);
+begin
+   if x > y then
+      Swap(x, y)
+end;
+
+procedure MinMax(const x, y : integer; out min, max : integer);
+begin
+   min := x;
+   max := y;
+   Order(min, max)
+end;
+
+function Add(x : integer; var total : integer) : integer;
+begin
+   total := total + x;
+   x := 0;
+   Add := total
+end;
+
+begin
+   a := 7;
+   b := 2;
+   Swap(a, b);
+   MinMax(9, 4, lo, hi);
+   sum := 1;
+   Add(a, sum);
+   Add(b, sum)
+end.
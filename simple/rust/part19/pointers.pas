@@ -0,0 +1,102 @@
+program Pointers;
+
+type
+   PNode = ^Node;
+   Node = record
+      value : integer;
+      next : PNode;
+   end;
+   PTree = ^Tree;
+   Tree = record
+      key : integer;
+      left, right : PTree;
+   end;
+
+var
+   head, current : PNode;
+   root : PTree;
+   p : ^integer;
+   i : integer;
+
+procedure Push(var list : PNode; value : integer);
+var
+   item : PNode;
+begin
+   new(item);
+   item^.value := value;
+   item^.next := list;
+   list := item
+end;
+
+procedure Insert(var tree : PTree; key : integer);
+var
+   leaf, parent : PTree;
+begin
+   new(leaf);
+   leaf^.key := key;
+   leaf^.left := nil;
+   leaf^.right := nil;
+   if tree = nil then
+      tree := leaf
+   else
+   begin
+      parent := tree;
+      while ((key < parent^.key) and (parent^.left <> nil))
+         or ((key >= parent^.key) and (parent^.right <> nil)) do
+         if key < parent^.key then
+            parent := parent^.left
+         else
+            parent := parent^.right;
+      if key < parent^.key then
+         parent^.left := leaf
+      else
+         parent^.right := leaf
+   end
+end;
+
+function Contains(tree : PTree; key : integer) : boolean;
+begin
+   while (tree <> nil) and (tree^.key <> key) do
+      if key < tree^.key then
+         tree := tree^.left
+      else
+         tree := tree^.right;
+   Contains := tree <> nil
+end;
+
+begin
+   new(p);
+   p^ := 42;
+   inc(p^);
+   writeln('p^ = ', p^);
+   dispose(p);
+
+   head := nil;
+   for i := 1 to 5 do
+      Push(head, i * i);
+   current := head;
+   while current <> nil do
+   begin
+      write(current^.value, ' ');
+      current := current^.next
+   end;
+   writeln;
+   with head^ do
+      writeln('first: ', value);
+   while head <> nil do
+   begin
+      current := head;
+      head := head^.next;
+      dispose(current)
+   end;
+
+   root := nil;
+   Insert(root, 50);
+   Insert(root, 30);
+   Insert(root, 70);
+   Insert(root, 20);
+   Insert(root, 40);
+   Insert(root, 60);
+   writeln('smallest: ', root^.left^.left^.key, ', largest: ', root^.right^.key);
+   writeln('contains 60: ', Contains(root, 60), ', contains 65: ', Contains(root, 65))
+end.
@@ -0,0 +1,23 @@
+program Read;
+
+var
+   name : string;
+   a, b : integer;
+   r : real;
+   c : char;
+   values : array[1..3] of integer;
+   i : integer;
+
+begin
+   write('Name: ');
+   readln(name);
+   write('Two numbers: ');
+   read(a, b);
+   readln;
+   write('A char and a real: ');
+   readln(c, r);
+   for i := 1 to 3 do
+      read(values[i]);
+   writeln('Hello, ', name, '! ', a, ' + ', b, ' = ', a + b);
+   writeln(r:0:2, ' [', c, '] ', values[1] + values[2] + values[3])
+end.
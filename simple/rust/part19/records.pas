@@ -0,0 +1,55 @@
+program Records;
+
+type
+   TPoint = record
+      x, y : integer
+   end;
+   TRect = record
+      topLeft, bottomRight : TPoint;
+      visible : boolean;
+   end;
+   TPath = array[1..3] of TPoint;
+
+var
+   p : TPoint;
+   r : TRect;
+   path : TPath;
+   i, area : integer;
+
+procedure Move(var pt : TPoint; dx, dy : integer);
+begin
+   with pt do
+   begin
+      x := x + dx;
+      y := y + dy
+   end
+end;
+
+function Width(const rect : TRect) : integer;
+begin
+   with rect, bottomRight do
+      Width := x - topLeft.x
+end;
+
+begin
+   p.x := 1;
+   p.Y := 2;
+   r.topLeft := p;
+   r.bottomRight.x := 11;
+   r.bottomRight.y := 7;
+   Move(r.topLeft, 2, 1);
+
+   with r do
+   begin
+      visible := true;
+      area := Width(r) * (bottomRight.y - topLeft.y)
+   end;
+
+   for i := 1 to 3 do
+      with path[i] do
+      begin
+         x := i;
+         y := x * 10
+      end;
+   Move(path[2], 5, 5)
+end.
@@ -0,0 +1,61 @@
+program Recursion;
+
+var
+   moves : integer;
+
+function Fib(n : integer) : integer;
+begin
+   if n < 2 then
+      Fib := n
+   else
+      Fib := Fib(n - 1) + Fib(n - 2)
+end;
+
+function Factorial(n : integer) : integer;
+begin
+   if n = 0 then
+      Factorial := 1
+   else
+      Factorial := n * Factorial(n - 1)
+end;
+
+{ mutually recursive functions need a forward declaration }
+function IsOdd(n : integer) : boolean; forward;
+
+function IsEven(n : integer) : boolean;
+begin
+   if n = 0 then
+      IsEven := true
+   else
+      IsEven := IsOdd(n - 1)
+end;
+
+{ the full declaration may leave out the header given forward }
+function IsOdd;
+begin
+   if n = 0 then
+      IsOdd := false
+   else
+      IsOdd := IsEven(n - 1)
+end;
+
+procedure Hanoi(n : integer; source, target, spare : char; var moves : integer);
+begin
+   if n > 0 then
+   begin
+      Hanoi(n - 1, source, spare, target, moves);
+      moves := moves + 1;
+      if n = 3 then
+         writeln('move the largest disk from ', source, ' to ', target);
+      Hanoi(n - 1, spare, target, source, moves)
+   end
+end;
+
+begin
+   writeln('fib(25) = ', Fib(25));
+   writeln('10! = ', Factorial(10));
+   writeln('7 is odd: ', IsOdd(7), ', 10 is even: ', IsEven(10));
+   moves := 0;
+   Hanoi(3, 'A', 'C', 'B', moves);
+   writeln('moves: ', moves)
+end.
@@ -0,0 +1,57 @@
+program Sets;
+
+const
+   VowelSet = ['A', 'E', 'I', 'O', 'U'];
+
+type
+   Day = (Mon, Tue, Wed, Thu, Fri, Sat, Sun);
+   Days = set of Day;
+   Digits = set of 0..9;
+
+var
+   weekend, workdays, all, meetings : Days;
+   even, odds, small : Digits;
+   sentence : string;
+   i, vowels, letters, others : integer;
+   d : Day;
+
+begin
+   weekend := [Sat, Sun];
+   workdays := [Mon..Fri];
+   all := weekend + workdays;
+   meetings := [Tue, Thu, Sat];
+   write('meetings on workdays:');
+   for d := Mon to Sun do
+      if d in meetings * workdays then
+         write(' ', d);
+   writeln;
+   writeln('all days: ', all = [Mon..Sun], ', weekend within all: ', weekend <= all,
+           ', all covers workdays: ', all >= workdays);
+   writeln('free workdays: ', (workdays - meetings) = [Mon, Wed, Fri]);
+
+   even := [0, 2, 4, 6, 8];
+   odds := [0..9] - even;
+   small := [];
+   for i := 0 to 4 do
+      small := small + [i];
+   write('odds:');
+   for i := 0 to 9 do
+      if i in odds then
+         write(' ', i);
+   writeln;
+   writeln('small even: ', small * even = [0, 2, 4], ', disjoint: ', even * odds = [],
+           ', not equal: ', even <> odds);
+
+   sentence := 'Sets are part of Standard Pascal!';
+   vowels := 0;
+   letters := 0;
+   others := 0;
+   for i := 1 to length(sentence) do
+      if upcase(sentence[i]) in VowelSet then
+         vowels := vowels + 1
+      else if sentence[i] in ['a'..'z', 'A'..'Z'] then
+         letters := letters + 1
+      else if not (sentence[i] in [' ']) then
+         others := others + 1;
+   writeln('vowels: ', vowels, ', consonants: ', letters, ', others: ', others)
+end.
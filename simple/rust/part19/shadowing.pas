@@ -0,0 +1,31 @@
+program Shadowing;
+
+{ the names of built-in routines can be declared again by the program }
+const
+   Val = 3;
+
+type
+   Str = string;
+
+var
+   length, pos, high : integer;
+   eof : boolean;
+   name : Str;
+
+procedure Show(s : string);
+var
+   length : integer;
+begin
+   length := 2;
+   writeln(s, ': ', length)
+end;
+
+begin
+   length := 10;
+   pos := 4;
+   high := length * Val;
+   eof := false;
+   name := 'shadowed';
+   writeln('length = ', length, ', pos = ', pos, ', high = ', high, ', eof = ', eof);
+   Show(name)
+end.
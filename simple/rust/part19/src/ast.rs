@@ -1,18 +1,133 @@
+use crate::builtins::BuiltIn;
+use crate::calc::CalcResult;
 use crate::token;
 use crate::var_type::VarType;
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+// type as it is written in the source, replaced with the
+// resolved type during the semantic analysis
+#[derive(Debug, Clone)]
+pub enum TypeSpec {
+    Named {
+        id: String,
+        token: token::Token,
+    },
+    Subrange {
+        low: Box<AST>,
+        high: Box<AST>,
+        token: token::Token,
+    },
+    Enum {
+        values: Vec<String>,
+        token: token::Token,
+    },
+    Array {
+        index: Vec<TypeSpec>,
+        element: Box<TypeSpec>,
+        token: token::Token,
+    },
+    // the fields are variable declarations
+    Record {
+        fields: Vec<AST>,
+    },
+    Set {
+        element: Box<TypeSpec>,
+        token: token::Token,
+    },
+    // the pointed type may be declared later in the same block
+    Pointer {
+        id: String,
+        token: token::Token,
+    },
+    Resolved(VarType),
+}
+
+impl TypeSpec {
+    pub fn var_type(&self) -> &VarType {
+        match self {
+            TypeSpec::Resolved(var_type) => var_type,
+            any => panic!("unresolved type {:?}", any),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeSpec {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TypeSpec::Named { id, .. } => write!(fmt, "{}", id),
+            TypeSpec::Subrange { .. } => write!(fmt, "subrange"),
+            TypeSpec::Enum { values, .. } => write!(fmt, "({})", values.join(", ")),
+            TypeSpec::Array { .. } => write!(fmt, "array"),
+            TypeSpec::Record { .. } => write!(fmt, "record"),
+            TypeSpec::Set { element, .. } => write!(fmt, "set of {}", element),
+            TypeSpec::Pointer { id, .. } => write!(fmt, "^{}", id),
+            TypeSpec::Resolved(var_type) => write!(fmt, "{}", var_type),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamMode {
+    VALUE,
+    VAR,
+    CONST,
+    OUT,
+}
 
 #[derive(Debug, Clone)]
 pub struct Param {
     pub id: String,
-    pub ttype: VarType,
+    pub ttype: TypeSpec,
+    pub mode: ParamMode,
 }
 
 impl std::fmt::Display for Param {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "name='{}', type='{}'", self.id, self.ttype)
+        write!(fmt, "name='{}', type='{}'", self.id, self.ttype)?;
+        if self.mode != ParamMode::VALUE {
+            write!(fmt, ", mode='{:?}'", self.mode)?;
+        }
+        Ok(())
+    }
+}
+
+// the block of a routine shared by its declaration and all its calls, it is
+// set when the block is analyzed so that calls made from the routine itself
+// or through a forward declaration run it as well
+#[derive(Clone, Default)]
+pub struct RoutineBody(Rc<OnceCell<AST>>);
+
+impl RoutineBody {
+    pub fn get(&self) -> Option<&AST> {
+        self.0.get()
+    }
+
+    pub fn set(&self, block: AST) {
+        let _ = self.0.set(block);
+    }
+}
+
+// the block may contain calls of the routine itself
+impl std::fmt::Debug for RoutineBody {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "RoutineBody")
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct CaseLabel {
+    pub low: AST,
+    pub high: Option<AST>,
+    pub token: token::Token,
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseBranch {
+    pub labels: Vec<CaseLabel>,
+    pub body: AST,
+}
+
 #[derive(Debug, Clone)]
 pub enum AST {
     Program {
@@ -23,50 +138,199 @@ pub enum AST {
         declaration_nodes: Vec<AST>,
         compound_nodes: Box<AST>,
     },
+    // labels are numbers or identifiers, numbers are kept as their decimal text
+    LabelDecl {
+        label: String,
+        token: token::Token,
+    },
+    ConstDecl {
+        id: String,
+        value: Box<AST>,
+        token: token::Token,
+    },
+    TypeDecl {
+        id: String,
+        type_spec: TypeSpec,
+        token: token::Token,
+    },
     VarDecl {
         id: String,
-        var_type: VarType,
+        var_type: TypeSpec,
         token: token::Token,
     },
+    // the block of a forward declaration is given later
     ProcedureDecl {
         id: String,
         params: Vec<Param>,
-        block_node: Box<AST>,
+        block_node: Option<Box<AST>>,
+        token: token::Token,
     },
     ProcedureCall {
         id: String,
         ast_params: Vec<AST>,
         params: Vec<Param>,
         token: token::Token,
-        body: Option<Box<AST>>,
+        body: Option<RoutineBody>,
         scope_level: usize,
     },
+    FunctionDecl {
+        id: String,
+        params: Vec<Param>,
+        // left out only in the full declaration of a forward function
+        return_type: Option<TypeSpec>,
+        block_node: Option<Box<AST>>,
+        token: token::Token,
+    },
+    // the type of the result is set by the semantic analysis
+    FunctionCall {
+        id: String,
+        ast_params: Vec<AST>,
+        params: Vec<Param>,
+        return_type: Option<VarType>,
+        token: token::Token,
+        body: Option<RoutineBody>,
+        scope_level: usize,
+    },
+    // a call of a routine provided by the interpreter
+    BuiltInCall {
+        builtin: BuiltIn,
+        args: Vec<AST>,
+        arg_types: Vec<VarType>,
+        token: token::Token,
+    },
+    // an argument of write with the width and the precision like x:10:2
+    Format {
+        expr: Box<AST>,
+        width: Box<AST>,
+        precision: Option<Box<AST>>,
+        token: token::Token,
+    },
     NumInteger {
         value: i32,
     },
     NumReal {
-        value: f32,
+        value: f64,
+    },
+    Boolean {
+        value: bool,
+    },
+    Char {
+        value: char,
+    },
+    Str {
+        value: String,
+    },
+    // a set constructor like [1, 3..5], the elements are single values or ranges
+    Set {
+        elements: Vec<(AST, Option<AST>)>,
+        token: token::Token,
+    },
+    // a folded constant which has no literal of its own, the type
+    // is kept since a set value does not know the type of its elements
+    Constant {
+        value: CalcResult,
+        var_type: VarType,
+    },
+    // an implicit conversion of an assigned value, like an integer to a real
+    Convert {
+        expr: Box<AST>,
+        var_type: VarType,
+    },
+    // a value stored to a subrange variable must be within its bounds
+    RangeCheck {
+        expr: Box<AST>,
+        var_type: VarType,
+        token: token::Token,
     },
     BinOp {
         left: Box<AST>,
         right: Box<AST>,
         op: token::Kind,
+        token: token::Token,
     },
     UnaryOp {
         op: token::Kind,
         expr: Box<AST>,
+        token: token::Token,
     },
     Compound {
         children: Vec<AST>,
     },
+    If {
+        condition: Box<AST>,
+        then_node: Box<AST>,
+        else_node: Option<Box<AST>>,
+        token: token::Token,
+    },
+    While {
+        condition: Box<AST>,
+        body: Box<AST>,
+        token: token::Token,
+    },
+    Repeat {
+        children: Vec<AST>,
+        condition: Box<AST>,
+        token: token::Token,
+    },
+    Case {
+        selector: Box<AST>,
+        branches: Vec<CaseBranch>,
+        else_nodes: Option<Vec<AST>>,
+        token: token::Token,
+    },
+    For {
+        var_id: String,
+        var: Box<AST>,
+        start: Box<AST>,
+        end: Box<AST>,
+        downto: bool,
+        body: Box<AST>,
+        token: token::Token,
+    },
     Assign {
-        left_id: String,
         left: Box<AST>,
         right: Box<AST>,
+        token: token::Token,
     },
+    // the scope level the variable is declared on is set by the semantic analysis
     Var {
         id: String,
         token: token::Token,
+        scope_level: usize,
+    },
+    // an element of an array, several indexes are nested nodes
+    Index {
+        array: Box<AST>,
+        index: Box<AST>,
+        token: token::Token,
+    },
+    Field {
+        record: Box<AST>,
+        field: String,
+        token: token::Token,
+    },
+    // the variable a pointer points to
+    Deref {
+        pointer: Box<AST>,
+        token: token::Token,
+    },
+    Nil,
+    // the record is accessed inside of the body by the alias
+    With {
+        record: Box<AST>,
+        alias: String,
+        body: Box<AST>,
+        token: token::Token,
+    },
+    // a statement a goto can jump to
+    Labeled {
+        label: String,
+        statement: Box<AST>,
+        token: token::Token,
+    },
+    Goto {
+        label: String,
+        token: token::Token,
     },
     NoOp,
 }
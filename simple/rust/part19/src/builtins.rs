@@ -0,0 +1,337 @@
+use crate::calc::CalcResult;
+use crate::var_type::VarType;
+
+// routines provided by the interpreter itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuiltIn {
+    WRITE,
+    WRITELN,
+    READ,
+    READLN,
+    ABS,
+    SQR,
+    SQRT,
+    SIN,
+    COS,
+    ARCTAN,
+    EXP,
+    LN,
+    ROUND,
+    TRUNC,
+    ODD,
+    ORD,
+    CHR,
+    SUCC,
+    PRED,
+    LOW,
+    HIGH,
+    INC,
+    DEC,
+    LENGTH,
+    COPY,
+    POS,
+    CONCAT,
+    INSERT,
+    DELETE,
+    UPCASE,
+    INTTOSTR,
+    STRTOINT,
+    VAL,
+    STR,
+    NEW,
+    DISPOSE,
+    ASSIGN,
+    RESET,
+    REWRITE,
+    APPEND,
+    CLOSE,
+    EOF,
+    EOLN,
+    IORESULT,
+    EXIT,
+    BREAK,
+    CONTINUE,
+    HALT,
+}
+
+impl BuiltIn {
+    pub const ALL: [BuiltIn; 48] = [
+        BuiltIn::WRITE,
+        BuiltIn::WRITELN,
+        BuiltIn::READ,
+        BuiltIn::READLN,
+        BuiltIn::ABS,
+        BuiltIn::SQR,
+        BuiltIn::SQRT,
+        BuiltIn::SIN,
+        BuiltIn::COS,
+        BuiltIn::ARCTAN,
+        BuiltIn::EXP,
+        BuiltIn::LN,
+        BuiltIn::ROUND,
+        BuiltIn::TRUNC,
+        BuiltIn::ODD,
+        BuiltIn::ORD,
+        BuiltIn::CHR,
+        BuiltIn::SUCC,
+        BuiltIn::PRED,
+        BuiltIn::LOW,
+        BuiltIn::HIGH,
+        BuiltIn::INC,
+        BuiltIn::DEC,
+        BuiltIn::LENGTH,
+        BuiltIn::COPY,
+        BuiltIn::POS,
+        BuiltIn::CONCAT,
+        BuiltIn::INSERT,
+        BuiltIn::DELETE,
+        BuiltIn::UPCASE,
+        BuiltIn::INTTOSTR,
+        BuiltIn::STRTOINT,
+        BuiltIn::VAL,
+        BuiltIn::STR,
+        BuiltIn::NEW,
+        BuiltIn::DISPOSE,
+        BuiltIn::ASSIGN,
+        BuiltIn::RESET,
+        BuiltIn::REWRITE,
+        BuiltIn::APPEND,
+        BuiltIn::CLOSE,
+        BuiltIn::EOF,
+        BuiltIn::EOLN,
+        BuiltIn::IORESULT,
+        BuiltIn::EXIT,
+        BuiltIn::BREAK,
+        BuiltIn::CONTINUE,
+        BuiltIn::HALT,
+    ];
+
+    pub fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+// a real result must be a number, like an integer result must fit into an integer
+fn real(value: f64) -> Option<CalcResult> {
+    if value.is_finite() {
+        Some(CalcResult::FLOAT(value))
+    } else {
+        None
+    }
+}
+
+fn integer(value: f64) -> Option<CalcResult> {
+    if value >= i32::MIN as f64 && value <= i32::MAX as f64 {
+        Some(CalcResult::INTEGER(value as i32))
+    } else {
+        None
+    }
+}
+
+// the value which is `n` positions after the given one in its ordinal type,
+// returns None when it is outside of the type
+pub fn ordinal_step(value: &CalcResult, n: i32) -> Option<CalcResult> {
+    let ordinal = value.as_ordinal().checked_add(n)?;
+    if let Some((low, high)) = value.var_type().bounds() {
+        if ordinal < low || ordinal > high {
+            return None;
+        }
+    }
+    Some(value.with_ordinal(ordinal))
+}
+
+// the first or the last value of an ordinal type or of the index of an array
+pub fn bound(var_type: &VarType, high: bool) -> Option<CalcResult> {
+    match var_type {
+        VarType::ARRAY { index, .. } => bound(index, high),
+        VarType::INTEGER => Some(CalcResult::INTEGER(if high { i32::MAX } else { i32::MIN })),
+        var_type => {
+            let (low, high_ordinal) = var_type.bounds()?;
+            let ordinal = if high { high_ordinal } else { low };
+            Some(CalcResult::default_of(&var_type.base()).with_ordinal(ordinal))
+        }
+    }
+}
+
+// strings are indexed from 1, the parts of the text outside of it are ignored
+fn copy(text: &str, index: i32, count: i32) -> String {
+    text.chars()
+        .skip(index.max(1) as usize - 1)
+        .take(count.max(0) as usize)
+        .collect()
+}
+
+fn pos(substring: &str, text: &str) -> i32 {
+    match text.find(substring) {
+        Some(at) if !substring.is_empty() => text[..at].chars().count() as i32 + 1,
+        _ => 0,
+    }
+}
+
+pub fn insert(source: &str, text: &str, index: i32) -> String {
+    let at = index.max(1) as usize - 1;
+    let mut result: String = text.chars().take(at).collect();
+    result.push_str(source);
+    result.extend(text.chars().skip(at));
+    result
+}
+
+pub fn delete(text: &str, index: i32, count: i32) -> String {
+    if index < 1 || count <= 0 {
+        return text.to_string();
+    }
+    let at = index as usize - 1;
+    text.chars()
+        .take(at)
+        .chain(text.chars().skip(at.saturating_add(count as usize)))
+        .collect()
+}
+
+// parses an integer or a real the way Val does: leading blanks are skipped,
+// the error is the 1-based position of the first invalid character
+pub fn parse_number(text: &str, var_type: &VarType) -> Result<CalcResult, usize> {
+    let chars: Vec<char> = text.chars().collect();
+    let skip_digits = |mut pos: usize| {
+        while pos < chars.len() && chars[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        pos
+    };
+    let start = chars.iter().take_while(|ch| ch.is_whitespace()).count();
+    let mut pos = start;
+    if matches!(chars.get(pos), Some('+' | '-')) {
+        pos += 1;
+    }
+    let digits = pos;
+    pos = skip_digits(pos);
+    if pos == digits {
+        return Err(pos + 1);
+    }
+    if *var_type == VarType::REAL {
+        if chars.get(pos) == Some(&'.') {
+            pos = skip_digits(pos + 1);
+        }
+        if matches!(chars.get(pos), Some('e' | 'E')) {
+            pos += 1;
+            if matches!(chars.get(pos), Some('+' | '-')) {
+                pos += 1;
+            }
+            let exponent = pos;
+            pos = skip_digits(pos);
+            if pos == exponent {
+                return Err(pos + 1);
+            }
+        }
+    }
+    if pos < chars.len() {
+        return Err(pos + 1);
+    }
+    // a number which does not fit is invalid as a whole
+    let number: String = chars[start..].iter().collect();
+    match var_type {
+        VarType::INTEGER => number
+            .parse()
+            .map(CalcResult::INTEGER)
+            .map_err(|_| chars.len()),
+        _ => number.parse().ok().and_then(real).ok_or(chars.len()),
+    }
+}
+
+// computes the result of a built-in function from already type checked arguments,
+// returns None when the operation is invalid for the arguments
+pub fn evaluate(builtin: BuiltIn, args: &[CalcResult]) -> Option<CalcResult> {
+    match (builtin, args) {
+        (BuiltIn::ABS, [CalcResult::INTEGER(value)]) => {
+            Some(CalcResult::INTEGER(value.wrapping_abs()))
+        }
+        (BuiltIn::ABS, [value]) => real(value.as_f64().abs()),
+        (BuiltIn::SQR, [CalcResult::INTEGER(value)]) => {
+            Some(CalcResult::INTEGER(value.wrapping_mul(*value)))
+        }
+        (BuiltIn::SQR, [value]) => real(value.as_f64() * value.as_f64()),
+        (BuiltIn::SQRT, [value]) => real(value.as_f64().sqrt()),
+        (BuiltIn::SIN, [value]) => real(value.as_f64().sin()),
+        (BuiltIn::COS, [value]) => real(value.as_f64().cos()),
+        (BuiltIn::ARCTAN, [value]) => real(value.as_f64().atan()),
+        (BuiltIn::EXP, [value]) => real(value.as_f64().exp()),
+        (BuiltIn::LN, [value]) if value.as_f64() > 0.0 => real(value.as_f64().ln()),
+        (BuiltIn::ROUND, [value]) => integer(value.as_f64().round()),
+        (BuiltIn::TRUNC, [value]) => integer(value.as_f64().trunc()),
+        (BuiltIn::ODD, [value]) => Some(CalcResult::BOOLEAN(value.as_ordinal() % 2 != 0)),
+        (BuiltIn::ORD, [value]) => Some(CalcResult::INTEGER(value.as_ordinal())),
+        (BuiltIn::CHR, [value]) => ordinal_step(&CalcResult::CHAR('\0'), value.as_ordinal()),
+        (BuiltIn::SUCC, [value]) => ordinal_step(value, 1),
+        (BuiltIn::PRED, [value]) => ordinal_step(value, -1),
+        (BuiltIn::LENGTH, [text]) => {
+            Some(CalcResult::INTEGER(text.as_string().chars().count() as i32))
+        }
+        (BuiltIn::COPY, [text, index, count]) => Some(CalcResult::STRING(copy(
+            &text.as_string(),
+            index.as_ordinal(),
+            count.as_ordinal(),
+        ))),
+        (BuiltIn::POS, [substring, text]) => Some(CalcResult::INTEGER(pos(
+            &substring.as_string(),
+            &text.as_string(),
+        ))),
+        (BuiltIn::CONCAT, texts) => Some(CalcResult::STRING(
+            texts.iter().map(CalcResult::as_string).collect(),
+        )),
+        (BuiltIn::UPCASE, [CalcResult::CHAR(value)]) => {
+            Some(CalcResult::CHAR(value.to_ascii_uppercase()))
+        }
+        (BuiltIn::UPCASE, [text]) => {
+            Some(CalcResult::STRING(text.as_string().to_ascii_uppercase()))
+        }
+        (BuiltIn::INTTOSTR, [value]) => Some(CalcResult::STRING(value.to_string())),
+        (BuiltIn::STRTOINT, [text]) => parse_number(&text.as_string(), &VarType::INTEGER).ok(),
+        // the analyzer replaces the argument with the bound itself
+        (BuiltIn::LOW | BuiltIn::HIGH, [value]) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+// formats a real in the scientific notation like ' 1.5000000000000000E+000'
+fn scientific(value: f64, decimals: usize) -> String {
+    if value.is_nan() {
+        return "Nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "+Inf" } else { "-Inf" }.to_string();
+    }
+    let formatted = format!("{:.*e}", decimals, value.abs());
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    format!(
+        "{}{}E{}{:03}",
+        if value.is_sign_negative() { '-' } else { ' ' },
+        mantissa,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+// formats a value the way write does: reals without the precision are written
+// in the scientific notation, the width pads the value with spaces on the left
+pub fn format_value(value: &CalcResult, width: Option<i32>, precision: Option<i32>) -> String {
+    let text = match (value, precision) {
+        (CalcResult::FLOAT(value), Some(precision)) => {
+            let precision = precision.max(0);
+            // halves are rounded away from zero like round does, rust rounds them to even
+            let scale = 10f64.powi(precision);
+            let rounded = (value * scale).round() / scale;
+            let value = if rounded.is_finite() { rounded } else { *value };
+            format!("{:.*}", precision as usize, value)
+        }
+        (CalcResult::FLOAT(value), None) => {
+            // the default width leaves sixteen digits after the point
+            let decimals = width.map_or(16, |width| (width - 8).max(1));
+            scientific(*value, decimals as usize)
+        }
+        (value, _) => value.to_string(),
+    };
+    match width {
+        Some(width) => format!("{:>1$}", text, width.max(0) as usize),
+        None => text,
+    }
+}
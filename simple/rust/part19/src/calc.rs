@@ -1,7 +1,30 @@
+use crate::token;
+use crate::var_type::VarType;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+// the ordinal numbers of set elements are from 0 to 255
+pub const SET_SIZE: i32 = 256;
+
 #[derive(Debug, Clone)]
 pub enum CalcResult {
-    FLOAT(f32),
+    FLOAT(f64),
     INTEGER(i32),
+    BOOLEAN(bool),
+    CHAR(char),
+    STRING(String),
+    // ordinal number of the value and its enumerated type
+    ENUM(i32, VarType),
+    // ordinal number of the first index and the elements
+    ARRAY { low: i32, items: Vec<CalcResult> },
+    // names and values of the fields
+    RECORD(Vec<(String, CalcResult)>),
+    // address of a dynamic variable on the heap, None is nil
+    POINTER(Option<usize>),
+    // ordinal numbers of the elements
+    SET(BTreeSet<i32>),
+    // index of the text file opened by the interpreter, None before assign
+    FILE(Option<usize>),
 }
 
 impl std::fmt::Display for CalcResult {
@@ -9,6 +32,299 @@ impl std::fmt::Display for CalcResult {
         match self {
             CalcResult::FLOAT(v) => write!(fmt, "{}", v),
             CalcResult::INTEGER(v) => write!(fmt, "{}", v),
+            CalcResult::BOOLEAN(v) => write!(fmt, "{}", if *v { "TRUE" } else { "FALSE" }),
+            CalcResult::CHAR(v) => write!(fmt, "{}", v),
+            CalcResult::STRING(v) => write!(fmt, "{}", v),
+            CalcResult::ENUM(v, var_type) => match var_type {
+                VarType::ENUM { values, .. } => write!(fmt, "{}", values[*v as usize]),
+                _ => write!(fmt, "{}", v),
+            },
+            CalcResult::ARRAY { items, .. } => {
+                let items = items.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                write!(fmt, "[{}]", items.join(", "))
+            }
+            CalcResult::RECORD(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect::<Vec<_>>();
+                write!(fmt, "({})", fields.join("; "))
+            }
+            CalcResult::POINTER(Some(address)) => write!(fmt, "^{}", address),
+            CalcResult::POINTER(None) => write!(fmt, "nil"),
+            CalcResult::SET(elements) => {
+                let elements = elements.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                write!(fmt, "[{}]", elements.join(", "))
+            }
+            CalcResult::FILE(_) => write!(fmt, "file"),
+        }
+    }
+}
+
+impl CalcResult {
+    pub fn var_type(&self) -> VarType {
+        match self {
+            CalcResult::FLOAT(_) => VarType::REAL,
+            CalcResult::INTEGER(_) => VarType::INTEGER,
+            CalcResult::BOOLEAN(_) => VarType::BOOLEAN,
+            CalcResult::CHAR(_) => VarType::CHAR,
+            CalcResult::STRING(_) => VarType::STRING,
+            CalcResult::ENUM(_, var_type) => var_type.clone(),
+            CalcResult::POINTER(None) => VarType::NIL,
+            any => panic!("impossible value {:?}", any),
+        }
+    }
+
+    // the value a variable of the type gets when it is declared
+    pub fn default_of(var_type: &VarType) -> CalcResult {
+        match var_type {
+            VarType::INTEGER => CalcResult::INTEGER(0),
+            VarType::REAL => CalcResult::FLOAT(0.0),
+            VarType::BOOLEAN => CalcResult::BOOLEAN(false),
+            VarType::CHAR => CalcResult::CHAR('\0'),
+            VarType::STRING => CalcResult::STRING(String::new()),
+            VarType::SUBRANGE { base, low, .. } => CalcResult::default_of(base).with_ordinal(*low),
+            VarType::ENUM { .. } => CalcResult::ENUM(0, var_type.clone()),
+            VarType::ARRAY { index, element } => {
+                let (low, high) = index.bounds().unwrap();
+                CalcResult::ARRAY {
+                    low,
+                    items: vec![CalcResult::default_of(element); (high - low + 1) as usize],
+                }
+            }
+            VarType::RECORD { fields, .. } => CalcResult::RECORD(
+                fields
+                    .iter()
+                    .map(|(name, var_type)| (name.clone(), CalcResult::default_of(var_type)))
+                    .collect(),
+            ),
+            VarType::POINTER { .. } | VarType::NIL => CalcResult::POINTER(None),
+            VarType::SET { .. } => CalcResult::SET(BTreeSet::new()),
+            VarType::TEXT => CalcResult::FILE(None),
+        }
+    }
+
+    pub fn item(&self, index: i32) -> Option<&CalcResult> {
+        match self {
+            CalcResult::ARRAY { low, items } => {
+                items.get(usize::try_from(index.checked_sub(*low)?).ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn field(&self, id: &str) -> Option<&CalcResult> {
+        match self {
+            CalcResult::RECORD(fields) => {
+                fields
+                    .iter()
+                    .find_map(|(name, value)| if name == id { Some(value) } else { None })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, id: &str) -> Option<&mut CalcResult> {
+        match self {
+            CalcResult::RECORD(fields) => {
+                fields
+                    .iter_mut()
+                    .find_map(|(name, value)| if name == id { Some(value) } else { None })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn item_mut(&mut self, index: i32) -> Option<&mut CalcResult> {
+        match self {
+            CalcResult::ARRAY { low, items } => {
+                items.get_mut(usize::try_from(index.checked_sub(*low)?).ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            CalcResult::FLOAT(value) => *value,
+            CalcResult::INTEGER(value) => *value as f64,
+            any => panic!("impossible value {:?}", any),
+        }
+    }
+
+    pub fn as_ordinal(&self) -> i32 {
+        match self {
+            CalcResult::INTEGER(value) => *value,
+            CalcResult::BOOLEAN(value) => *value as i32,
+            CalcResult::CHAR(value) => *value as i32,
+            CalcResult::ENUM(value, _) => *value,
+            any => panic!("impossible value {:?}", any),
+        }
+    }
+
+    pub fn as_string(&self) -> String {
+        match self {
+            CalcResult::STRING(value) => value.clone(),
+            CalcResult::CHAR(value) => value.to_string(),
+            any => panic!("impossible value {:?}", any),
+        }
+    }
+
+    // converts an assignment compatible value to the type of the variable
+    pub fn convert(self, var_type: &VarType) -> CalcResult {
+        match (var_type.base(), self) {
+            (VarType::REAL, CalcResult::INTEGER(value)) => CalcResult::FLOAT(value as f64),
+            (VarType::STRING, CalcResult::CHAR(value)) => CalcResult::STRING(value.to_string()),
+            (_, value) => value,
+        }
+    }
+
+    // builds a value of the same ordinal type with the given ordinal number
+    pub fn with_ordinal(&self, ordinal: i32) -> CalcResult {
+        match self {
+            CalcResult::INTEGER(_) => CalcResult::INTEGER(ordinal),
+            CalcResult::BOOLEAN(_) => CalcResult::BOOLEAN(ordinal != 0),
+            CalcResult::CHAR(_) => {
+                CalcResult::CHAR(char::from_u32(ordinal as u32).unwrap_or_default())
+            }
+            CalcResult::ENUM(_, var_type) => CalcResult::ENUM(ordinal, var_type.clone()),
+            any => panic!("impossible value {:?}", any),
+        }
+    }
+}
+
+fn arithmetic<I, F>(a: CalcResult, b: CalcResult, int_op: I, float_op: F) -> CalcResult
+where
+    I: Fn(i32, i32) -> i32,
+    F: Fn(f64, f64) -> f64,
+{
+    match (a, b) {
+        (CalcResult::INTEGER(a), CalcResult::INTEGER(b)) => CalcResult::INTEGER(int_op(a, b)),
+        (a, b) => CalcResult::FLOAT(float_op(a.as_f64(), b.as_f64())),
+    }
+}
+
+// builds a set of the elements from the ranges of ordinal numbers,
+// returns None when an element is out of the range of sets
+pub fn set_value(ranges: &[(i32, i32)]) -> Option<CalcResult> {
+    let mut elements = BTreeSet::new();
+    for &(low, high) in ranges {
+        if low <= high && (low < 0 || high >= SET_SIZE) {
+            return None;
+        }
+        elements.extend(low..=high);
+    }
+    Some(CalcResult::SET(elements))
+}
+
+fn set_operation(op: &token::Kind, a: &BTreeSet<i32>, b: &BTreeSet<i32>) -> CalcResult {
+    match op {
+        token::Kind::PLUS => CalcResult::SET(a.union(b).copied().collect()),
+        token::Kind::MUL => CalcResult::SET(a.intersection(b).copied().collect()),
+        token::Kind::MINUS => CalcResult::SET(a.difference(b).copied().collect()),
+        token::Kind::EQUAL => CalcResult::BOOLEAN(a == b),
+        token::Kind::NOT_EQUAL => CalcResult::BOOLEAN(a != b),
+        token::Kind::LESS_EQUAL => CalcResult::BOOLEAN(a.is_subset(b)),
+        token::Kind::GREATER_EQUAL => CalcResult::BOOLEAN(a.is_superset(b)),
+        any => panic!("impossible kind {:?}", any),
+    }
+}
+
+// the same operator is logical on booleans and bitwise on integers
+fn logical(a: CalcResult, b: CalcResult, op: fn(i32, i32) -> i32) -> CalcResult {
+    match (a, b) {
+        (CalcResult::BOOLEAN(a), CalcResult::BOOLEAN(b)) => {
+            CalcResult::BOOLEAN(op(a as i32, b as i32) != 0)
+        }
+        (a, b) => CalcResult::INTEGER(op(a.as_ordinal(), b.as_ordinal())),
+    }
+}
+
+fn compare(a: &CalcResult, b: &CalcResult) -> Option<Ordering> {
+    match (a, b) {
+        // pointers are only equal or not
+        (CalcResult::POINTER(a), CalcResult::POINTER(b)) => (a == b).then_some(Ordering::Equal),
+        (CalcResult::STRING(_), _) | (_, CalcResult::STRING(_)) => {
+            Some(a.as_string().cmp(&b.as_string()))
+        }
+        (CalcResult::FLOAT(_), _) | (_, CalcResult::FLOAT(_)) => {
+            a.as_f64().partial_cmp(&b.as_f64())
+        }
+        (a, b) => a.as_ordinal().partial_cmp(&b.as_ordinal()),
+    }
+}
+
+// applies a binary operator to already type checked operands,
+// returns None on division by zero
+pub fn binary_operation(op: &token::Kind, a: CalcResult, b: CalcResult) -> Option<CalcResult> {
+    if let (CalcResult::SET(a), CalcResult::SET(b)) = (&a, &b) {
+        return Some(set_operation(op, a, b));
+    }
+    let res = match op {
+        token::Kind::MUL => arithmetic(a, b, i32::wrapping_mul, |a, b| a * b),
+        token::Kind::FLOAT_DIV => {
+            if b.as_f64() == 0.0 {
+                return None;
+            }
+            CalcResult::FLOAT(a.as_f64() / b.as_f64())
+        }
+        token::Kind::INTEGER_DIV => {
+            if let CalcResult::INTEGER(0) = b {
+                return None;
+            }
+            arithmetic(a, b, i32::wrapping_div, |a, b| a / b)
+        }
+        token::Kind::MOD => {
+            if let CalcResult::INTEGER(0) = b {
+                return None;
+            }
+            arithmetic(a, b, i32::wrapping_rem, |a, b| a % b)
+        }
+        // the shift count is taken modulo 32, the right shift is logical
+        token::Kind::SHL => CalcResult::INTEGER(a.as_ordinal().wrapping_shl(b.as_ordinal() as u32)),
+        token::Kind::SHR => {
+            CalcResult::INTEGER((a.as_ordinal() as u32).wrapping_shr(b.as_ordinal() as u32) as i32)
+        }
+        token::Kind::IN => match b {
+            CalcResult::SET(elements) => CalcResult::BOOLEAN(elements.contains(&a.as_ordinal())),
+            any => panic!("impossible value {:?}", any),
+        },
+        token::Kind::AND => logical(a, b, |a, b| a & b),
+        token::Kind::OR => logical(a, b, |a, b| a | b),
+        token::Kind::XOR => logical(a, b, |a, b| a ^ b),
+        token::Kind::PLUS => match (a, b) {
+            (a @ (CalcResult::STRING(_) | CalcResult::CHAR(_)), b) => {
+                CalcResult::STRING(a.as_string() + &b.as_string())
+            }
+            (a, b) => arithmetic(a, b, i32::wrapping_add, |a, b| a + b),
+        },
+        token::Kind::MINUS => arithmetic(a, b, i32::wrapping_sub, |a, b| a - b),
+        token::Kind::EQUAL => CalcResult::BOOLEAN(compare(&a, &b) == Some(Ordering::Equal)),
+        token::Kind::NOT_EQUAL => CalcResult::BOOLEAN(compare(&a, &b) != Some(Ordering::Equal)),
+        token::Kind::LESS => CalcResult::BOOLEAN(compare(&a, &b) == Some(Ordering::Less)),
+        token::Kind::LESS_EQUAL => CalcResult::BOOLEAN(matches!(
+            compare(&a, &b),
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        token::Kind::GREATER => CalcResult::BOOLEAN(compare(&a, &b) == Some(Ordering::Greater)),
+        token::Kind::GREATER_EQUAL => CalcResult::BOOLEAN(matches!(
+            compare(&a, &b),
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        any => panic!("impossible kind {:?}", any),
+    };
+    Some(res)
+}
+
+pub fn unary_operation(op: &token::Kind, a: CalcResult) -> CalcResult {
+    match (op, a) {
+        (token::Kind::PLUS, a) => a,
+        (token::Kind::MINUS, CalcResult::INTEGER(value)) => {
+            CalcResult::INTEGER(value.wrapping_neg())
         }
+        (token::Kind::MINUS, CalcResult::FLOAT(value)) => CalcResult::FLOAT(-value),
+        (token::Kind::NOT, CalcResult::BOOLEAN(value)) => CalcResult::BOOLEAN(!value),
+        (token::Kind::NOT, CalcResult::INTEGER(value)) => CalcResult::INTEGER(!value),
+        (op, a) => panic!("impossible operation {:?} {:?}", op, a),
     }
 }
@@ -1,10 +1,37 @@
 use crate::calc::CalcResult;
 use std::collections::HashMap;
 
+// name of the variable holding the value returned by a function
+pub const RETURN_VALUE: &str = "Result";
+
 #[derive(Debug, Clone)]
 pub enum ARType {
     PROGRAM,
     PROCEDURE,
+    FUNCTION,
+}
+
+// part of a structured variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    INDEX(i32),
+    FIELD(String),
+}
+
+// where a referenced variable starts
+#[derive(Debug, Clone, PartialEq)]
+pub enum Root {
+    // the activation record the variable lives in and its name
+    VARIABLE { frame: usize, id: String },
+    // the address of a dynamic variable
+    HEAP(usize),
+}
+
+// location of a variable and the path to the element inside of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub root: Root,
+    pub path: Vec<Selector>,
 }
 
 #[derive(Debug, Clone)]
@@ -12,7 +39,11 @@ pub struct ActiveRecord {
     name: String,
     kind: ARType,
     nesting_level: usize,
+    // the static link to the record of the routine the running one is declared in
+    access_link: Option<usize>,
     members: HashMap<String, CalcResult>,
+    // parameters passed by reference point to variables of other records
+    references: HashMap<String, Reference>,
 }
 
 pub struct CallStack {
@@ -31,7 +62,12 @@ impl CallStack {
         CallStack { stack: Vec::new() }
     }
 
-    pub fn push(&mut self, ar: ActiveRecord) {
+    // a record is linked to the nearest record on the level above it, which is
+    // the record of the enclosing routine even when the call is recursive
+    pub fn push(&mut self, mut ar: ActiveRecord) {
+        if !self.stack.is_empty() {
+            ar.access_link = Some(self.frame(ar.nesting_level - 1));
+        }
         self.stack.push(ar);
     }
 
@@ -39,19 +75,167 @@ impl CallStack {
         self.stack.pop()
     }
 
-    pub fn top(&mut self) -> &mut ActiveRecord {
-        let last = self.stack.len() - 1;
-        &mut self.stack[last]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    // follows the static links from the top record to the record on the level
+    fn frame(&self, nesting_level: usize) -> usize {
+        let mut frame = self.stack.len() - 1;
+        while self.stack[frame].nesting_level > nesting_level {
+            frame = self.stack[frame].access_link.unwrap();
+        }
+        frame
+    }
+
+    // finds the variable declared on the scope level, it is accessed by the name
+    // in the record of the running routine or of one of the enclosing ones
+    pub fn resolve(&self, id: &str, scope_level: usize) -> Reference {
+        self.resolve_in(self.frame(scope_level), id)
+    }
+
+    // finds the variable declared in the running routine
+    pub fn local(&self, id: &str) -> Reference {
+        self.resolve_in(self.stack.len() - 1, id)
+    }
+
+    fn resolve_in(&self, frame: usize, id: &str) -> Reference {
+        match self.stack[frame].references.get(id) {
+            Some(reference) => reference.clone(),
+            None => Reference {
+                root: Root::VARIABLE {
+                    frame,
+                    id: id.to_string(),
+                },
+                path: Vec::new(),
+            },
+        }
+    }
+
+    // makes the name refer to the variable in the top record
+    pub fn bind(&mut self, id: &str, reference: Reference) {
+        let frame = self.stack.len() - 1;
+        self.stack[frame].insert_reference(id.to_string(), reference);
+    }
+
+    pub fn unbind(&mut self, id: &str) {
+        let frame = self.stack.len() - 1;
+        self.stack[frame].references.remove(id);
+    }
+
+    pub fn variable(&self, frame: usize, id: &str) -> Option<&CalcResult> {
+        self.stack[frame].members.get(id)
+    }
+
+    pub fn variable_mut(&mut self, frame: usize, id: &str) -> Option<&mut CalcResult> {
+        self.stack[frame].members.get_mut(id)
+    }
+
+    pub fn insert(&mut self, frame: usize, id: &str, value: CalcResult) {
+        self.stack[frame].members.insert(id.to_string(), value);
+    }
+}
+
+// finds the element of a variable, characters of strings are not stored on their own
+pub fn find<'a>(variable: &'a CalcResult, path: &[Selector]) -> Option<&'a CalcResult> {
+    let mut value = variable;
+    for selector in path {
+        value = match selector {
+            Selector::INDEX(index) => value.item(*index)?,
+            Selector::FIELD(id) => value.field(id)?,
+        };
+    }
+    Some(value)
+}
+
+pub fn load(variable: &CalcResult, path: &[Selector]) -> Option<CalcResult> {
+    if let Some((Selector::INDEX(index), path)) = path.split_last() {
+        if let Some(CalcResult::STRING(value)) = find(variable, path) {
+            return value.chars().nth(*index as usize - 1).map(CalcResult::CHAR);
+        }
+    }
+    find(variable, path).cloned()
+}
+
+pub fn store(variable: &mut CalcResult, path: &[Selector], value: CalcResult) {
+    let (last, path) = match path.split_last() {
+        Some(split) => split,
+        None => {
+            *variable = value;
+            return;
+        }
+    };
+    let mut target = variable;
+    for selector in path {
+        target = match selector {
+            Selector::INDEX(index) => target.item_mut(*index).unwrap(),
+            Selector::FIELD(id) => target.field_mut(id).unwrap(),
+        };
+    }
+    match (last, target) {
+        (Selector::INDEX(index), CalcResult::STRING(string)) => {
+            let mut chars: Vec<char> = string.chars().collect();
+            chars[*index as usize - 1] = value.as_string().chars().next().unwrap();
+            *string = chars.into_iter().collect();
+        }
+        (Selector::INDEX(index), target) => *target.item_mut(*index).unwrap() = value,
+        (Selector::FIELD(id), target) => *target.field_mut(id).unwrap() = value,
+    }
+}
+
+impl Reference {
+    // the reference to a dynamic variable
+    pub fn heap(address: usize) -> Reference {
+        Reference {
+            root: Root::HEAP(address),
+            path: Vec::new(),
+        }
+    }
+
+    // the reference to the element of the referenced array
+    pub fn index(mut self, index: i32) -> Reference {
+        self.path.push(Selector::INDEX(index));
+        self
+    }
+
+    // the reference to the field of the referenced record
+    pub fn field(mut self, id: &str) -> Reference {
+        self.path.push(Selector::FIELD(id.to_string()));
+        self
+    }
+}
+
+impl std::fmt::Display for Reference {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.root {
+            Root::VARIABLE { frame, id } => write!(fmt, "-> #{}:{}", frame + 1, id)?,
+            Root::HEAP(address) => write!(fmt, "-> ^{}", address)?,
+        }
+        for selector in &self.path {
+            match selector {
+                Selector::INDEX(index) => write!(fmt, "[{}]", index)?,
+                Selector::FIELD(id) => write!(fmt, ".{}", id)?,
+            }
+        }
+        Ok(())
     }
 }
 
 impl std::fmt::Display for ActiveRecord {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(fmt, "{}: {} {}", self.nesting_level, self.kind, self.name)?;
+        if let Some(link) = self.access_link {
+            write!(fmt, " (static link -> #{})", link + 1)?;
+        }
         let mut lines = self
             .members
             .iter()
             .map(|(a, b)| format!("{:>4}:{: >20}", a, b.to_string()))
+            .chain(
+                self.references
+                    .iter()
+                    .map(|(a, b)| format!("{:>4}:{: >20}", a, b.to_string())),
+            )
             .collect::<Vec<String>>();
         lines.sort();
         if !lines.is_empty() {
@@ -68,7 +252,9 @@ impl ActiveRecord {
             name,
             kind,
             nesting_level,
+            access_link: None,
             members: HashMap::new(),
+            references: HashMap::new(),
         }
     }
 
@@ -76,19 +262,24 @@ impl ActiveRecord {
         self.members.insert(id, res);
     }
 
-    pub fn get(&self, id: &String) -> Option<&CalcResult> {
-        self.members.get(id)
+    pub fn insert_reference(&mut self, id: String, reference: Reference) {
+        self.references.insert(id, reference);
+    }
+
+    pub fn return_value(&self) -> Option<&CalcResult> {
+        self.members.get(RETURN_VALUE)
     }
 }
 
 impl std::fmt::Display for CallStack {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // records are numbered from the bottom of the stack
         let lines = self
             .stack
             .iter()
-            .clone()
+            .enumerate()
             .rev()
-            .map(|x| x.to_string())
+            .map(|(frame, x)| format!("#{} {}", frame + 1, x))
             .collect::<Vec<String>>();
         writeln!(fmt, "CALL STACK")?;
         writeln!(fmt, "{}", lines.join("\n"))?;
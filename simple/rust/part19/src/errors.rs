@@ -1,4 +1,6 @@
+use crate::calc::CalcResult;
 use crate::token::Token;
+use crate::var_type::VarType;
 
 pub enum Error {
     #[allow(non_camel_case_types)]
@@ -9,6 +11,60 @@ pub enum Error {
     DUPLICATE_ID(Token),
     #[allow(non_camel_case_types)]
     INVALID_CHARACTER(char),
+    #[allow(non_camel_case_types)]
+    UNTERMINATED_STRING(Token),
+    #[allow(non_camel_case_types)]
+    TYPE_MISMATCH(Token),
+    #[allow(non_camel_case_types)]
+    INVALID_ASSIGNMENT(Token),
+    #[allow(non_camel_case_types)]
+    DIVISION_BY_ZERO(Token),
+    #[allow(non_camel_case_types)]
+    FOR_VARIABLE_ASSIGNMENT(Token),
+    #[allow(non_camel_case_types)]
+    CONSTANT_EXPECTED(Token),
+    #[allow(non_camel_case_types)]
+    DUPLICATE_CASE_LABEL(Token),
+    #[allow(non_camel_case_types)]
+    INVALID_RANGE(Token),
+    #[allow(non_camel_case_types)]
+    CASE_NO_MATCH(Token, CalcResult),
+    #[allow(non_camel_case_types)]
+    WRONG_ARGUMENTS_NUMBER(Token),
+    #[allow(non_camel_case_types)]
+    UNDEFINED_RESULT(Token),
+    #[allow(non_camel_case_types)]
+    VARIABLE_EXPECTED(Token),
+    #[allow(non_camel_case_types)]
+    TYPE_EXPECTED(Token),
+    #[allow(non_camel_case_types)]
+    INDEX_OUT_OF_RANGE(Token, CalcResult),
+    #[allow(non_camel_case_types)]
+    INVALID_INPUT(Token, VarType),
+    #[allow(non_camel_case_types)]
+    INVALID_OPERATION(Token),
+    #[allow(non_camel_case_types)]
+    RANGE_ERROR(Token),
+    #[allow(non_camel_case_types)]
+    INVALID_POINTER(Token),
+    #[allow(non_camel_case_types)]
+    IO_ERROR(Token, i32),
+    #[allow(non_camel_case_types)]
+    HEADER_MISMATCH(Token),
+    #[allow(non_camel_case_types)]
+    UNSOLVED_FORWARD(Token),
+    #[allow(non_camel_case_types)]
+    STACK_OVERFLOW(Token),
+    #[allow(non_camel_case_types)]
+    NOT_IN_LOOP(Token),
+    #[allow(non_camel_case_types)]
+    UNDECLARED_LABEL(Token),
+    #[allow(non_camel_case_types)]
+    DUPLICATE_LABEL(Token),
+    #[allow(non_camel_case_types)]
+    UNDEFINED_LABEL(Token),
+    #[allow(non_camel_case_types)]
+    INVALID_GOTO(Token),
 }
 
 impl std::fmt::Display for Error {
@@ -18,6 +74,55 @@ impl std::fmt::Display for Error {
             Error::ID_NOT_FOUND(token) => format!("Identifier not found: {}", token),
             Error::DUPLICATE_ID(token) => format!("Duplicate id found: {}", token),
             Error::INVALID_CHARACTER(ch) => format!("Invalid character: {}", ch),
+            Error::UNTERMINATED_STRING(token) => format!("String exceeds line: {}", token),
+            Error::TYPE_MISMATCH(token) => format!("Type mismatch: {}", token),
+            Error::INVALID_ASSIGNMENT(token) => format!("Invalid assignment target: {}", token),
+            Error::DIVISION_BY_ZERO(token) => format!("Division by zero: {}", token),
+            Error::FOR_VARIABLE_ASSIGNMENT(token) => {
+                format!("Illegal assignment to for-loop variable: {}", token)
+            }
+            Error::CONSTANT_EXPECTED(token) => format!("Constant expression expected: {}", token),
+            Error::DUPLICATE_CASE_LABEL(token) => format!("Duplicate case label: {}", token),
+            Error::INVALID_RANGE(token) => {
+                format!("Upper bound of range is less than lower bound: {}", token)
+            }
+            Error::CASE_NO_MATCH(token, value) => {
+                format!("Case value {} matches no label: {}", value, token)
+            }
+            Error::WRONG_ARGUMENTS_NUMBER(token) => {
+                format!("Wrong number of arguments: {}", token)
+            }
+            Error::UNDEFINED_RESULT(token) => {
+                format!("Function result is not assigned: {}", token)
+            }
+            Error::VARIABLE_EXPECTED(token) => format!("Variable identifier expected: {}", token),
+            Error::TYPE_EXPECTED(token) => format!("Type identifier expected: {}", token),
+            Error::INDEX_OUT_OF_RANGE(token, index) => {
+                format!("Index {} is out of range: {}", index, token)
+            }
+            Error::INVALID_INPUT(token, var_type) => {
+                format!("Invalid input, {} expected: {}", var_type, token)
+            }
+            Error::INVALID_OPERATION(token) => {
+                format!("Invalid floating point operation: {}", token)
+            }
+            Error::RANGE_ERROR(token) => format!("Range check error: {}", token),
+            Error::INVALID_POINTER(token) => format!("Invalid pointer operation: {}", token),
+            Error::IO_ERROR(token, code) => format!("I/O error {}: {}", code, token),
+            Error::HEADER_MISMATCH(token) => {
+                format!("Header does not match the forward declaration: {}", token)
+            }
+            Error::UNSOLVED_FORWARD(token) => format!("Forward declaration not solved: {}", token),
+            Error::STACK_OVERFLOW(token) => format!("Stack overflow: {}", token),
+            Error::NOT_IN_LOOP(token) => format!("Statement allowed only in a loop: {}", token),
+            Error::UNDECLARED_LABEL(token) => {
+                format!("Label not declared in the current block: {}", token)
+            }
+            Error::DUPLICATE_LABEL(token) => format!("Label defined more than once: {}", token),
+            Error::UNDEFINED_LABEL(token) => format!("Label used but not defined: {}", token),
+            Error::INVALID_GOTO(token) => {
+                format!("Goto into a nested statement is not allowed: {}", token)
+            }
         };
         write!(fmt, "{}", msg)?;
         Ok(())
@@ -0,0 +1,96 @@
+use crate::input::Input;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+
+// codes of I/O errors, the same as in Turbo Pascal
+pub const FILE_NOT_FOUND: i32 = 2;
+pub const ACCESS_DENIED: i32 = 5;
+pub const DISK_WRITE_ERROR: i32 = 101;
+pub const FILE_NOT_ASSIGNED: i32 = 102;
+pub const FILE_NOT_OPEN: i32 = 103;
+pub const NOT_OPEN_FOR_INPUT: i32 = 104;
+pub const NOT_OPEN_FOR_OUTPUT: i32 = 105;
+
+enum Mode {
+    CLOSED,
+    INPUT(Input),
+    OUTPUT(BufWriter<File>),
+}
+
+// a text file variable: the name given by assign and the file
+// opened by reset, rewrite or append
+pub struct TextFile {
+    name: String,
+    mode: Mode,
+}
+
+fn error_code(error: std::io::Error) -> i32 {
+    match error.kind() {
+        ErrorKind::PermissionDenied => ACCESS_DENIED,
+        _ => FILE_NOT_FOUND,
+    }
+}
+
+impl TextFile {
+    pub fn new(name: String) -> TextFile {
+        TextFile {
+            name,
+            mode: Mode::CLOSED,
+        }
+    }
+
+    pub fn assign(&mut self, name: String) {
+        self.name = name;
+    }
+
+    // opening a file closes it first, like in Turbo Pascal
+    pub fn reset(&mut self) -> Result<(), i32> {
+        let _ = self.close();
+        let file = File::open(&self.name).map_err(error_code)?;
+        self.mode = Mode::INPUT(Input::new(Box::new(BufReader::new(file))));
+        Ok(())
+    }
+
+    pub fn rewrite(&mut self) -> Result<(), i32> {
+        let _ = self.close();
+        let file = File::create(&self.name).map_err(error_code)?;
+        self.mode = Mode::OUTPUT(BufWriter::new(file));
+        Ok(())
+    }
+
+    pub fn append(&mut self) -> Result<(), i32> {
+        let _ = self.close();
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&self.name)
+            .map_err(error_code)?;
+        self.mode = Mode::OUTPUT(BufWriter::new(file));
+        Ok(())
+    }
+
+    pub fn close(&mut self) -> Result<(), i32> {
+        match std::mem::replace(&mut self.mode, Mode::CLOSED) {
+            Mode::CLOSED => Err(FILE_NOT_OPEN),
+            Mode::INPUT(_) => Ok(()),
+            Mode::OUTPUT(mut writer) => writer.flush().map_err(|_| DISK_WRITE_ERROR),
+        }
+    }
+
+    pub fn input(&mut self) -> Result<&mut Input, i32> {
+        match &mut self.mode {
+            Mode::INPUT(input) => Ok(input),
+            Mode::OUTPUT(_) => Err(NOT_OPEN_FOR_INPUT),
+            Mode::CLOSED => Err(FILE_NOT_OPEN),
+        }
+    }
+
+    pub fn write(&mut self, text: &str) -> Result<(), i32> {
+        match &mut self.mode {
+            Mode::OUTPUT(writer) => writer
+                .write_all(text.as_bytes())
+                .map_err(|_| DISK_WRITE_ERROR),
+            Mode::INPUT(_) => Err(NOT_OPEN_FOR_OUTPUT),
+            Mode::CLOSED => Err(FILE_NOT_OPEN),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::calc::CalcResult;
+
+// dynamic variables created by new, addresses of disposed variables
+// are not reused so that dangling pointers can be detected
+pub struct Heap {
+    cells: Vec<Option<CalcResult>>,
+}
+
+impl Heap {
+    pub fn new() -> Heap {
+        Heap { cells: Vec::new() }
+    }
+
+    pub fn allocate(&mut self, value: CalcResult) -> usize {
+        self.cells.push(Some(value));
+        self.cells.len() - 1
+    }
+
+    // returns false when there is no variable at the address
+    pub fn dispose(&mut self, address: usize) -> bool {
+        match self.cells.get_mut(address) {
+            Some(cell @ Some(_)) => {
+                *cell = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, address: usize) -> Option<&CalcResult> {
+        self.cells.get(address)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, address: usize) -> Option<&mut CalcResult> {
+        self.cells.get_mut(address)?.as_mut()
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::BufRead;
+
+// text read by lines, the line is kept until all its characters are read
+pub struct Input {
+    reader: Box<dyn BufRead>,
+    line: Vec<char>,
+    pos: usize,
+}
+
+impl Input {
+    pub fn new(reader: Box<dyn BufRead>) -> Input {
+        Input {
+            reader,
+            line: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    pub fn stdin() -> Input {
+        Input::new(Box::new(std::io::stdin().lock()))
+    }
+
+    // reads the next line when the current one is over, returns false at the end of the text
+    fn fill(&mut self) -> bool {
+        if self.pos < self.line.len() {
+            return true;
+        }
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) | Err(_) => false,
+            Ok(_) => {
+                self.line = line.chars().collect();
+                self.pos = 0;
+                true
+            }
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<char> {
+        if self.fill() {
+            Some(self.line[self.pos])
+        } else {
+            None
+        }
+    }
+
+    pub fn next_char(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    // reads the rest of the line, the end of the line is left unread
+    pub fn read_string(&mut self) -> String {
+        let mut result = String::new();
+        while let Some(ch) = self.peek() {
+            if ch == '\n' {
+                break;
+            }
+            result.push(ch);
+            self.pos += 1;
+        }
+        if result.ends_with('\r') {
+            result.pop();
+        }
+        result
+    }
+
+    // skips blanks and line ends and reads the characters up to the next blank
+    pub fn read_word(&mut self) -> String {
+        while let Some(ch) = self.peek() {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.pos += 1;
+        }
+        let mut result = String::new();
+        while let Some(ch) = self.peek() {
+            if ch.is_whitespace() {
+                break;
+            }
+            result.push(ch);
+            self.pos += 1;
+        }
+        result
+    }
+
+    pub fn skip_line(&mut self) {
+        while let Some(ch) = self.next_char() {
+            if ch == '\n' {
+                break;
+            }
+        }
+    }
+}
@@ -1,39 +1,115 @@
-use crate::ast::AST;
-use crate::calc::CalcResult;
-use crate::callstack::{ARType, ActiveRecord, CallStack};
+use crate::ast::{Param, ParamMode, RoutineBody, AST};
+use crate::builtins::{
+    delete, evaluate, format_value, insert, ordinal_step, parse_number, BuiltIn,
+};
+use crate::calc::{binary_operation, set_value, unary_operation, CalcResult};
+use crate::callstack::{self, ARType, ActiveRecord, CallStack, Reference, Root, RETURN_VALUE};
 use crate::errors::Error;
+use crate::files::{TextFile, FILE_NOT_ASSIGNED};
+use crate::heap::Heap;
+use crate::input::Input;
 use crate::token;
+use crate::var_type::VarType;
+use std::io::Write;
+
+// the stack of the thread running the interpreter, it is reserved but only used pages take memory
+pub const STACK_SIZE: usize = 1 << 32;
+
+// the stack taken by one level of recursion, measured with a debug build: a call in
+// an expression took about 116 KiB, one nested in a loop, a case and an expression
+// about 240 KiB, a release build takes less than a tenth of it
+const CALL_STACK_USE: usize = 256 << 10;
+
+// recursion deeper than this is stopped before the interpreter runs out of its stack
+const MAX_DEPTH: usize = STACK_SIZE / CALL_STACK_USE;
 
 impl CalcResult {
-    fn as_f32(&self) -> f32 {
+    fn as_bool(&self) -> bool {
         match self {
-            CalcResult::FLOAT(value) => *value,
-            CalcResult::INTEGER(value) => *value as f32,
+            CalcResult::BOOLEAN(value) => *value,
+            any => panic!("impossible value {:?}", any),
         }
     }
 }
 
-fn at_least_one_float(a: CalcResult, b: CalcResult) -> bool {
-    match a {
-        CalcResult::FLOAT(_) => true,
-        _ => matches!(b, CalcResult::FLOAT(_)),
+// the reasons why statements stop running before their end
+#[derive(Debug)]
+enum Interrupt {
+    // boxed to keep the results of the interpreter small
+    ERROR(Box<Error>),
+    BREAK,
+    CONTINUE,
+    // leaves the running routine, the result of a function is already set
+    EXIT,
+    HALT(i32),
+    // jumps to the labeled statement in one of the enclosing statement sequences
+    GOTO(String),
+}
+
+impl From<Error> for Interrupt {
+    fn from(error: Error) -> Interrupt {
+        Interrupt::ERROR(Box::new(error))
+    }
+}
+
+// whether the statement has the label, a statement may have several labels
+fn has_label(statement: &AST, label: &str) -> bool {
+    match statement {
+        AST::Labeled {
+            label: own,
+            statement,
+            ..
+        } => own == label || has_label(statement, label),
+        _ => false,
     }
 }
 
-fn arithmetic<T>(a: CalcResult, b: CalcResult, op: T) -> CalcResult
-where
-    T: Fn(f32, f32) -> f32,
-{
-    let result = op(a.as_f32(), b.as_f32());
-    if at_least_one_float(a, b) {
-        CalcResult::FLOAT(result)
-    } else {
-        CalcResult::INTEGER(result as i32)
+// parses the input as a value of the type
+fn read_value(
+    input: &mut Input,
+    var_type: &VarType,
+    token: &token::Token,
+) -> Result<CalcResult, Error> {
+    let invalid = || Error::INVALID_INPUT(token.clone(), var_type.base());
+    let value = match var_type.base() {
+        VarType::INTEGER => input
+            .read_word()
+            .parse()
+            .map(CalcResult::INTEGER)
+            .map_err(|_| invalid()),
+        VarType::REAL => input
+            .read_word()
+            .parse()
+            .map(CalcResult::FLOAT)
+            .map_err(|_| invalid()),
+        VarType::CHAR => input.next_char().map(CalcResult::CHAR).ok_or_else(invalid),
+        VarType::STRING => Ok(CalcResult::STRING(input.read_string())),
+        any => panic!("impossible type {:?}", any),
+    }?;
+    in_range(value, var_type, token)
+}
+
+// checks a value stored without an assignment against the range of a subrange variable
+fn in_range(
+    value: CalcResult,
+    var_type: &VarType,
+    token: &token::Token,
+) -> Result<CalcResult, Error> {
+    match var_type {
+        VarType::SUBRANGE { .. } if !var_type.contains(value.as_ordinal()) => {
+            Err(Error::RANGE_ERROR(token.clone()))
+        }
+        _ => Ok(value),
     }
 }
 
 pub struct Interpreteter {
     callstack: CallStack,
+    heap: Heap,
+    input: Input,
+    files: Vec<TextFile>,
+    // the code of the last I/O error while the checks are off
+    io_result: i32,
     debug_stack: bool,
 }
 
@@ -42,6 +118,10 @@ impl Interpreteter {
         let callstack = CallStack::new();
         Interpreteter {
             callstack,
+            heap: Heap::new(),
+            input: Input::stdin(),
+            files: Vec::new(),
+            io_result: 0,
             debug_stack,
         }
     }
@@ -52,24 +132,479 @@ impl Interpreteter {
         }
     }
 
-    pub fn interpret(mut self, tree: AST) -> Result<(), Error> {
+    // the call stack is formatted only when it is shown
+    fn log_stack(&self) {
+        if self.debug_stack {
+            println!("{}", self.callstack);
+        }
+    }
+
+    // returns the exit code of the program, which is given by halt
+    pub fn interpret(mut self, tree: &AST) -> Result<i32, Error> {
         //println!("*** Tree: ***\n{:?}", tree);
-        self.visit_node(tree)?;
+        match self.visit_node(tree) {
+            Ok(_) => Ok(0),
+            Err(Interrupt::HALT(code)) => Ok(code),
+            Err(Interrupt::ERROR(error)) => Err(*error),
+            Err(interrupt) => panic!("impossible interrupt {:?}", interrupt),
+        }
+    }
+
+    // the variable the reference starts at, on the call stack or on the heap
+    fn root(&self, root: &Root) -> Option<&CalcResult> {
+        match root {
+            Root::VARIABLE { frame, id } => self.callstack.variable(*frame, id),
+            Root::HEAP(address) => self.heap.get(*address),
+        }
+    }
+
+    fn get(&self, reference: &Reference) -> Option<&CalcResult> {
+        callstack::find(self.root(&reference.root)?, &reference.path)
+    }
+
+    fn load(&self, reference: &Reference) -> Option<CalcResult> {
+        callstack::load(self.root(&reference.root)?, &reference.path)
+    }
+
+    fn store(&mut self, reference: &Reference, value: CalcResult) {
+        let variable = match &reference.root {
+            // scalar variables are created by the first assignment
+            Root::VARIABLE { frame, id } if reference.path.is_empty() => {
+                self.callstack.insert(*frame, id, value);
+                return;
+            }
+            Root::VARIABLE { frame, id } => self.callstack.variable_mut(*frame, id),
+            Root::HEAP(address) => self.heap.get_mut(*address),
+        };
+        callstack::store(variable.unwrap(), &reference.path, value);
+    }
+
+    // finds the variable or the array element the node refers to
+    fn reference(&mut self, node: &AST) -> Result<Reference, Interrupt> {
+        match node {
+            AST::Var {
+                id, scope_level, ..
+            } => Ok(self.callstack.resolve(id, *scope_level)),
+            AST::Index {
+                array,
+                index,
+                token,
+            } => {
+                let reference = self.reference(array)?;
+                let index = self.visit_node(index)?.unwrap();
+                match self.get(&reference) {
+                    // characters of a string are counted from one
+                    Some(CalcResult::STRING(value))
+                        if (1..=value.chars().count() as i32).contains(&index.as_ordinal()) =>
+                    {
+                        Ok(reference.index(index.as_ordinal()))
+                    }
+                    Some(array) if array.item(index.as_ordinal()).is_some() => {
+                        Ok(reference.index(index.as_ordinal()))
+                    }
+                    Some(_) => Err(Error::INDEX_OUT_OF_RANGE(token.clone(), index).into()),
+                    None => Err(Error::ID_NOT_FOUND(token.clone()).into()),
+                }
+            }
+            AST::Field {
+                record,
+                field,
+                token,
+            } => {
+                let reference = self.reference(record)?;
+                match self.get(&reference) {
+                    Some(_) => Ok(reference.field(field)),
+                    None => Err(Error::ID_NOT_FOUND(token.clone()).into()),
+                }
+            }
+            // nil and disposed pointers point to nothing
+            AST::Deref { pointer, token } => match self.visit_node(pointer)?.unwrap() {
+                CalcResult::POINTER(Some(address)) if self.heap.get(address).is_some() => {
+                    Ok(Reference::heap(address))
+                }
+                _ => Err(Error::INVALID_POINTER(token.clone()).into()),
+            },
+            any => panic!("impossible node {:?}", any),
+        }
+    }
+
+    // runs a routine provided by the interpreter, when the I/O checks are off
+    // an I/O error is kept for IOResult and the program goes on
+    fn builtin(
+        &mut self,
+        builtin: BuiltIn,
+        args: &[AST],
+        arg_types: &[VarType],
+        token: &token::Token,
+    ) -> Result<Option<CalcResult>, Interrupt> {
+        match self.run_builtin(builtin, args, arg_types, token) {
+            Err(Interrupt::ERROR(error)) if !token.io_checks => match *error {
+                Error::IO_ERROR(_, code) => {
+                    self.io_result = code;
+                    Ok(match builtin {
+                        BuiltIn::EOF | BuiltIn::EOLN => Some(CalcResult::BOOLEAN(true)),
+                        _ => None,
+                    })
+                }
+                error => Err(error.into()),
+            },
+            result => result,
+        }
+    }
+
+    fn run_builtin(
+        &mut self,
+        builtin: BuiltIn,
+        args: &[AST],
+        arg_types: &[VarType],
+        token: &token::Token,
+    ) -> Result<Option<CalcResult>, Interrupt> {
+        match builtin {
+            BuiltIn::WRITE | BuiltIn::WRITELN => {
+                let (file, args) = match arg_types.first() {
+                    Some(VarType::TEXT) => (Some(self.file(&args[0], token)?), &args[1..]),
+                    _ => (None, args),
+                };
+                let mut text = String::new();
+                for arg in args {
+                    text.push_str(&self.write_argument(arg)?);
+                }
+                if builtin == BuiltIn::WRITELN {
+                    text.push('\n');
+                }
+                match file {
+                    Some(index) => self.files[index]
+                        .write(&text)
+                        .map_err(|code| Error::IO_ERROR(token.clone(), code))?,
+                    None => {
+                        let mut stdout = std::io::stdout();
+                        stdout.write_all(text.as_bytes()).unwrap();
+                        stdout.flush().unwrap();
+                    }
+                }
+                Ok(None)
+            }
+            BuiltIn::READ | BuiltIn::READLN => {
+                let (file, args, arg_types) = match arg_types.first() {
+                    Some(VarType::TEXT) => (
+                        Some(self.file(&args[0], token)?),
+                        &args[1..],
+                        &arg_types[1..],
+                    ),
+                    _ => (None, args, arg_types),
+                };
+                // the file must be open for input even when nothing is read
+                self.input(file, token)?;
+                for (arg, var_type) in args.iter().zip(arg_types) {
+                    let reference = self.reference(arg)?;
+                    let value = read_value(self.input(file, token)?, var_type, token)?;
+                    self.store(&reference, value);
+                }
+                if builtin == BuiltIn::READLN {
+                    self.input(file, token)?.skip_line();
+                }
+                Ok(None)
+            }
+            BuiltIn::ASSIGN => {
+                let (reference, value) = self.variable(&args[0], token)?;
+                let name = self.visit_node(&args[1])?.unwrap().as_string();
+                match value {
+                    CalcResult::FILE(Some(index)) => self.files[index].assign(name),
+                    _ => {
+                        self.files.push(TextFile::new(name));
+                        let index = self.files.len() - 1;
+                        self.store(&reference, CalcResult::FILE(Some(index)));
+                    }
+                }
+                Ok(None)
+            }
+            BuiltIn::RESET | BuiltIn::REWRITE | BuiltIn::APPEND | BuiltIn::CLOSE => {
+                let index = self.file(&args[0], token)?;
+                let file = &mut self.files[index];
+                match builtin {
+                    BuiltIn::RESET => file.reset(),
+                    BuiltIn::REWRITE => file.rewrite(),
+                    BuiltIn::APPEND => file.append(),
+                    _ => file.close(),
+                }
+                .map_err(|code| Error::IO_ERROR(token.clone(), code))?;
+                Ok(None)
+            }
+            BuiltIn::EOF | BuiltIn::EOLN => {
+                let file = match args.first() {
+                    Some(arg) => Some(self.file(arg, token)?),
+                    None => None,
+                };
+                let next = self.input(file, token)?.peek();
+                Ok(Some(CalcResult::BOOLEAN(match builtin {
+                    BuiltIn::EOF => next.is_none(),
+                    _ => matches!(next, None | Some('\n' | '\r')),
+                })))
+            }
+            BuiltIn::IORESULT => Ok(Some(CalcResult::INTEGER(std::mem::take(
+                &mut self.io_result,
+            )))),
+            BuiltIn::EXIT => {
+                // the value given to exit becomes the result of the function
+                if let Some(value) = args.first() {
+                    let value = self.visit_node(value)?.unwrap();
+                    let reference = self.callstack.local(RETURN_VALUE);
+                    self.store(&reference, value);
+                }
+                Err(Interrupt::EXIT)
+            }
+            BuiltIn::BREAK => Err(Interrupt::BREAK),
+            BuiltIn::CONTINUE => Err(Interrupt::CONTINUE),
+            BuiltIn::HALT => {
+                let code = match args.first() {
+                    Some(code) => self.visit_node(code)?.unwrap().as_ordinal(),
+                    None => 0,
+                };
+                Err(Interrupt::HALT(code))
+            }
+            BuiltIn::INC | BuiltIn::DEC => {
+                let (reference, value) = self.variable(&args[0], token)?;
+                let amount = match args.get(1) {
+                    Some(amount) => self.visit_node(amount)?.unwrap().as_ordinal(),
+                    None => 1,
+                };
+                let amount = match builtin {
+                    BuiltIn::INC => Some(amount),
+                    _ => amount.checked_neg(),
+                };
+                match amount
+                    .and_then(|amount| ordinal_step(&value, amount))
+                    .filter(|value| arg_types[0].contains(value.as_ordinal()))
+                {
+                    Some(value) => self.store(&reference, value),
+                    None => return Err(Error::RANGE_ERROR(token.clone()).into()),
+                }
+                Ok(None)
+            }
+            BuiltIn::NEW => {
+                let reference = self.reference(&args[0])?;
+                let address = self.heap.allocate(CalcResult::default_of(&arg_types[0]));
+                self.store(&reference, CalcResult::POINTER(Some(address)));
+                Ok(None)
+            }
+            BuiltIn::DISPOSE => match self.visit_node(&args[0])?.unwrap() {
+                CalcResult::POINTER(Some(address)) if self.heap.dispose(address) => Ok(None),
+                _ => Err(Error::INVALID_POINTER(token.clone()).into()),
+            },
+            BuiltIn::INSERT => {
+                let source = self.visit_node(&args[0])?.unwrap().as_string();
+                let (reference, text) = self.variable(&args[1], token)?;
+                let index = self.visit_node(&args[2])?.unwrap().as_ordinal();
+                let text = insert(&source, &text.as_string(), index);
+                self.store(&reference, CalcResult::STRING(text));
+                Ok(None)
+            }
+            BuiltIn::DELETE => {
+                let (reference, text) = self.variable(&args[0], token)?;
+                let index = self.visit_node(&args[1])?.unwrap().as_ordinal();
+                let count = self.visit_node(&args[2])?.unwrap().as_ordinal();
+                let text = delete(&text.as_string(), index, count);
+                self.store(&reference, CalcResult::STRING(text));
+                Ok(None)
+            }
+            BuiltIn::VAL => {
+                // the variable keeps its value when the text is not a number
+                let text = self.visit_node(&args[0])?.unwrap().as_string();
+                let code = match parse_number(&text, &arg_types[0].base()) {
+                    Ok(value) => {
+                        let value = in_range(value, &arg_types[0], token)?;
+                        let reference = self.reference(&args[1])?;
+                        self.store(&reference, value);
+                        0
+                    }
+                    Err(position) => position as i32,
+                };
+                let reference = self.reference(&args[2])?;
+                self.store(&reference, CalcResult::INTEGER(code));
+                Ok(None)
+            }
+            BuiltIn::STR => {
+                let text = self.write_argument(&args[0])?;
+                let reference = self.reference(&args[1])?;
+                self.store(&reference, CalcResult::STRING(text));
+                Ok(None)
+            }
+            // functions which only compute their result from the arguments
+            _ => {
+                let mut values = Vec::new();
+                for arg in args {
+                    values.push(self.visit_node(arg)?.unwrap());
+                }
+                match evaluate(builtin, &values) {
+                    Some(value) => Ok(Some(value)),
+                    None => Err(Interrupt::from(match builtin {
+                        BuiltIn::CHR | BuiltIn::SUCC | BuiltIn::PRED => {
+                            Error::RANGE_ERROR(token.clone())
+                        }
+                        BuiltIn::STRTOINT => Error::INVALID_INPUT(token.clone(), VarType::INTEGER),
+                        _ => Error::INVALID_OPERATION(token.clone()),
+                    })),
+                }
+            }
+        }
+    }
+
+    // a variable modified by a built-in procedure and its current value
+    fn variable(
+        &mut self,
+        arg: &AST,
+        token: &token::Token,
+    ) -> Result<(Reference, CalcResult), Interrupt> {
+        let reference = self.reference(arg)?;
+        match self.load(&reference) {
+            Some(value) => Ok((reference, value)),
+            None => Err(Error::ID_NOT_FOUND(token.clone()).into()),
+        }
+    }
+
+    // the index of the text file stored in the variable
+    fn file(&mut self, arg: &AST, token: &token::Token) -> Result<usize, Interrupt> {
+        match self.visit_node(arg)?.unwrap() {
+            CalcResult::FILE(Some(index)) => Ok(index),
+            _ => Err(Error::IO_ERROR(token.clone(), FILE_NOT_ASSIGNED).into()),
+        }
+    }
+
+    // the text read by read and eof, the standard input when no file is given
+    fn input(&mut self, file: Option<usize>, token: &token::Token) -> Result<&mut Input, Error> {
+        match file {
+            Some(index) => self.files[index]
+                .input()
+                .map_err(|code| Error::IO_ERROR(token.clone(), code)),
+            None => Ok(&mut self.input),
+        }
+    }
+
+    fn write_argument(&mut self, arg: &AST) -> Result<String, Interrupt> {
+        match arg {
+            AST::Format {
+                expr,
+                width,
+                precision,
+                ..
+            } => {
+                let value = self.visit_node(expr)?.unwrap();
+                let width = self.visit_node(width)?.unwrap().as_ordinal();
+                let precision = match precision {
+                    Some(precision) => Some(self.visit_node(precision)?.unwrap().as_ordinal()),
+                    None => None,
+                };
+                Ok(format_value(&value, Some(width), precision))
+            }
+            arg => {
+                let value = self.visit_node(arg)?.unwrap();
+                Ok(format_value(&value, None, None))
+            }
+        }
+    }
+
+    // runs a statement sequence, a goto to one of its labeled
+    // statements goes on with the sequence from there
+    fn statements(&mut self, statements: &[AST]) -> Result<(), Interrupt> {
+        let mut next = 0;
+        while let Some(statement) = statements.get(next) {
+            match self.visit_node(statement) {
+                Ok(_) => next += 1,
+                Err(Interrupt::GOTO(label)) => {
+                    match statements.iter().position(|s| has_label(s, &label)) {
+                        Some(index) => next = index,
+                        None => return Err(Interrupt::GOTO(label)),
+                    }
+                }
+                Err(interrupt) => return Err(interrupt),
+            }
+        }
+        Ok(())
+    }
+
+    // runs the statements of a loop once, continue skips the rest of them,
+    // returns false when the loop is left by break
+    fn loop_body(&mut self, statements: &[AST]) -> Result<bool, Interrupt> {
+        match self.statements(statements) {
+            Ok(_) | Err(Interrupt::CONTINUE) => Ok(true),
+            Err(Interrupt::BREAK) => Ok(false),
+            Err(interrupt) => Err(interrupt),
+        }
+    }
+
+    fn check_depth(&self, token: &token::Token) -> Result<(), Error> {
+        if self.callstack.depth() >= MAX_DEPTH {
+            return Err(Error::STACK_OVERFLOW(token.clone()));
+        }
         Ok(())
     }
 
-    fn visit_node(&mut self, node: AST) -> Result<Option<CalcResult>, Error> {
+    // runs the body of a procedure or a function, which has the return type,
+    // in a new activation record
+    fn call(
+        &mut self,
+        id: &str,
+        return_type: Option<&VarType>,
+        params: &[Param],
+        ast_params: &[AST],
+        body: &AST,
+        scope_level: usize,
+    ) -> Result<ActiveRecord, Interrupt> {
+        let kind = match return_type {
+            Some(_) => ARType::FUNCTION,
+            None => ARType::PROCEDURE,
+        };
+        let mut ar = ActiveRecord::new(id.to_string(), kind.clone(), scope_level + 1);
+        // a structured result is created with all its elements, so that
+        // they can be assigned one by one like in a variable
+        if let Some(return_type @ (VarType::ARRAY { .. } | VarType::RECORD { .. })) = return_type {
+            ar.insert(
+                RETURN_VALUE.to_string(),
+                CalcResult::default_of(return_type),
+            );
+        }
+        for (formal, actual) in params.iter().zip(ast_params) {
+            match formal.mode {
+                ParamMode::VAR | ParamMode::OUT => {
+                    let reference = self.reference(actual)?;
+                    ar.insert_reference(formal.id.clone(), reference);
+                }
+                ParamMode::VALUE | ParamMode::CONST => {
+                    let res = self.visit_node(actual)?;
+                    ar.insert(formal.id.clone(), res.unwrap());
+                }
+            }
+        }
+        self.callstack.push(ar);
+        self.log(format!("ENTER: {} {}", kind, id));
+        self.log_stack();
+
+        match self.visit_node(body) {
+            Ok(_) | Err(Interrupt::EXIT) => {}
+            Err(interrupt) => return Err(interrupt),
+        }
+
+        self.log(format!("LEAVE: {} {}", kind, id));
+        self.log_stack();
+        Ok(self.callstack.pop().unwrap())
+    }
+
+    fn visit_node(&mut self, node: &AST) -> Result<Option<CalcResult>, Interrupt> {
         match node {
             AST::Program { block, name } => {
                 self.log(format!("ENTER: PROGRAM {}", name));
                 let ar = ActiveRecord::new(name.clone(), ARType::PROGRAM, 1);
                 self.callstack.push(ar);
-                self.log(self.callstack.to_string());
-                let res = self.visit_node(*block);
+                self.log_stack();
+                let res = self.visit_node(block);
                 self.log(format!("LEAVE: PROGRAM {}", name));
-                self.log(self.callstack.to_string());
+                self.log_stack();
                 self.callstack.pop();
-                res
+                // exit in the main block ends the program
+                match res {
+                    Err(Interrupt::EXIT) => Ok(None),
+                    res => res,
+                }
             }
             AST::Block {
                 declaration_nodes,
@@ -78,94 +613,259 @@ impl Interpreteter {
                 for declaration in declaration_nodes {
                     self.visit_node(declaration)?;
                 }
-                self.visit_node(*compound_nodes)
-            }
-            AST::VarDecl { .. } => Ok(None),
-            AST::NumInteger { value } => Ok(Some(CalcResult::INTEGER(value))),
-            AST::NumReal { value } => Ok(Some(CalcResult::FLOAT(value))),
-            AST::BinOp { left, right, op } => match op {
-                token::Kind::MUL => Ok(Some(arithmetic(
-                    self.visit_node(*left)?.unwrap(),
-                    self.visit_node(*right)?.unwrap(),
-                    |a, b| a * b,
-                ))),
-                token::Kind::FLOAT_DIV => {
-                    let a = self.visit_node(*left)?.unwrap();
-                    let b = self.visit_node(*right)?.unwrap();
-                    let c = a.as_f32() / b.as_f32();
-                    Ok(Some(CalcResult::FLOAT(c)))
-                }
-                token::Kind::INTEGER_DIV => Ok(Some(arithmetic(
-                    self.visit_node(*left)?.unwrap(),
-                    self.visit_node(*right)?.unwrap(),
-                    |a, b| a / b,
-                ))),
-                token::Kind::PLUS => Ok(Some(arithmetic(
-                    self.visit_node(*left)?.unwrap(),
-                    self.visit_node(*right)?.unwrap(),
-                    |a, b| a + b,
-                ))),
-                token::Kind::MINUS => Ok(Some(arithmetic(
-                    self.visit_node(*left)?.unwrap(),
-                    self.visit_node(*right)?.unwrap(),
-                    |a, b| a - b,
-                ))),
-                any => panic!("impossible kind {:?}", any),
-            },
-            AST::UnaryOp { op, expr } => match op {
-                token::Kind::PLUS => self.visit_node(*expr),
-                token::Kind::MINUS => {
-                    let res = match self.visit_node(*expr)?.unwrap() {
-                        CalcResult::INTEGER(value) => CalcResult::INTEGER(-value),
-                        CalcResult::FLOAT(value) => CalcResult::FLOAT(-value),
+                self.visit_node(compound_nodes)
+            }
+            AST::VarDecl { id, var_type, .. } => {
+                // structured variables are created with all their elements, files
+                // are not assigned yet and pointers are nil, other variables stay unset
+                if let VarType::ARRAY { .. }
+                | VarType::RECORD { .. }
+                | VarType::TEXT
+                | VarType::POINTER { .. } = var_type.var_type()
+                {
+                    let reference = self.callstack.local(id);
+                    self.store(&reference, CalcResult::default_of(var_type.var_type()));
+                }
+                Ok(None)
+            }
+            AST::LabelDecl { .. } | AST::ConstDecl { .. } | AST::TypeDecl { .. } => Ok(None),
+            AST::BuiltInCall {
+                builtin,
+                args,
+                arg_types,
+                token,
+            } => self.builtin(*builtin, args, arg_types, token),
+            AST::Format { expr, .. } => self.visit_node(expr),
+            AST::NumInteger { value } => Ok(Some(CalcResult::INTEGER(*value))),
+            AST::NumReal { value } => Ok(Some(CalcResult::FLOAT(*value))),
+            AST::Boolean { value } => Ok(Some(CalcResult::BOOLEAN(*value))),
+            AST::Char { value } => Ok(Some(CalcResult::CHAR(*value))),
+            AST::Str { value } => Ok(Some(CalcResult::STRING(value.clone()))),
+            AST::Nil => Ok(Some(CalcResult::POINTER(None))),
+            AST::Set { elements, token } => {
+                let mut ranges = Vec::new();
+                for (low, high) in elements {
+                    let low = self.visit_node(low)?.unwrap().as_ordinal();
+                    let high = match high {
+                        Some(high) => self.visit_node(high)?.unwrap().as_ordinal(),
+                        None => low,
                     };
-                    Ok(Some(res))
+                    ranges.push((low, high));
                 }
-                any => panic!("impossible kind {:?}", any),
-            },
+                match set_value(&ranges) {
+                    Some(value) => Ok(Some(value)),
+                    None => Err(Error::RANGE_ERROR(token.clone()).into()),
+                }
+            }
+            AST::Convert { expr, var_type } => {
+                let value = self.visit_node(expr)?.unwrap();
+                Ok(Some(value.convert(var_type)))
+            }
+            AST::Constant { value, .. } => Ok(Some(value.clone())),
+            AST::RangeCheck {
+                expr,
+                var_type,
+                token,
+            } => {
+                let value = self.visit_node(expr)?.unwrap();
+                if var_type.contains(value.as_ordinal()) {
+                    Ok(Some(value))
+                } else {
+                    Err(Error::RANGE_ERROR(token.clone()).into())
+                }
+            }
+            AST::BinOp {
+                left,
+                right,
+                op,
+                token,
+            } => {
+                let a = self.visit_node(left)?.unwrap();
+                // the right operand is skipped when the left one decides a boolean result
+                if let (token::Kind::AND, CalcResult::BOOLEAN(false))
+                | (token::Kind::OR, CalcResult::BOOLEAN(true)) = (op, &a)
+                {
+                    return Ok(Some(a));
+                }
+                let b = self.visit_node(right)?.unwrap();
+                match binary_operation(op, a, b) {
+                    Some(res) => Ok(Some(res)),
+                    None => Err(Error::DIVISION_BY_ZERO(token.clone()).into()),
+                }
+            }
+            AST::UnaryOp { op, expr, .. } => {
+                let a = self.visit_node(expr)?.unwrap();
+                Ok(Some(unary_operation(op, a)))
+            }
             AST::Compound { children } => {
-                for child in children {
-                    let _res = self.visit_node(child);
+                self.statements(children)?;
+                Ok(None)
+            }
+            AST::If {
+                condition,
+                then_node,
+                else_node,
+                ..
+            } => {
+                if self.visit_node(condition)?.unwrap().as_bool() {
+                    self.visit_node(then_node)?;
+                } else if let Some(else_node) = else_node {
+                    self.visit_node(else_node)?;
+                }
+                Ok(None)
+            }
+            AST::While {
+                condition, body, ..
+            } => {
+                while self.visit_node(condition)?.unwrap().as_bool() {
+                    if !self.loop_body(std::slice::from_ref(body))? {
+                        break;
+                    }
+                }
+                Ok(None)
+            }
+            AST::Repeat {
+                children,
+                condition,
+                ..
+            } => {
+                loop {
+                    if !self.loop_body(children)? {
+                        break;
+                    }
+                    if self.visit_node(condition)?.unwrap().as_bool() {
+                        break;
+                    }
+                }
+                Ok(None)
+            }
+            AST::Case {
+                selector,
+                branches,
+                else_nodes,
+                token,
+            } => {
+                let value = self.visit_node(selector)?.unwrap();
+                let ordinal = value.as_ordinal();
+                for branch in branches {
+                    for label in &branch.labels {
+                        let low = self.visit_node(&label.low)?.unwrap().as_ordinal();
+                        let high = match &label.high {
+                            Some(high) => self.visit_node(high)?.unwrap().as_ordinal(),
+                            None => low,
+                        };
+                        if low <= ordinal && ordinal <= high {
+                            self.visit_node(&branch.body)?;
+                            return Ok(None);
+                        }
+                    }
+                }
+                match else_nodes {
+                    Some(else_nodes) => {
+                        self.statements(else_nodes)?;
+                        Ok(None)
+                    }
+                    None => Err(Error::CASE_NO_MATCH(token.clone(), value).into()),
+                }
+            }
+            AST::For {
+                var,
+                start,
+                end,
+                downto,
+                body,
+                ..
+            } => {
+                // the bounds are evaluated only once, before the first iteration
+                let start = self.visit_node(start)?.unwrap();
+                let end = self.visit_node(end)?.unwrap().as_ordinal();
+                let step = if *downto { -1 } else { 1 };
+                let mut ordinal = start.as_ordinal();
+                while (*downto && ordinal >= end) || (!*downto && ordinal <= end) {
+                    let reference = self.reference(var)?;
+                    self.store(&reference, start.with_ordinal(ordinal));
+                    if !self.loop_body(std::slice::from_ref(body))? {
+                        break;
+                    }
+                    if ordinal == end {
+                        break;
+                    }
+                    ordinal += step;
                 }
                 Ok(None)
             }
-            AST::Assign { left_id, right, .. } => {
-                let res = self.visit_node(*right)?;
-                self.callstack.top().insert(left_id, res.unwrap());
+            AST::Assign { left, right, .. } => {
+                let res = self.visit_node(right)?;
+                let reference = self.reference(left)?;
+                self.store(&reference, res.unwrap());
                 Ok(None)
             }
-            AST::Var { id, token } => {
-                if let Some(value) = self.callstack.top().get(&id) {
-                    Ok(Some(value.clone()))
+            AST::Var { token, .. }
+            | AST::Index { token, .. }
+            | AST::Field { token, .. }
+            | AST::Deref { token, .. } => {
+                let reference = self.reference(node)?;
+                if let Some(value) = self.load(&reference) {
+                    Ok(Some(value))
                 } else {
-                    Err(Error::ID_NOT_FOUND(token))
+                    Err(Error::ID_NOT_FOUND(token.clone()).into())
                 }
             }
+            AST::With {
+                record,
+                alias,
+                body,
+                ..
+            } => {
+                // the record is found once, before the body is run
+                let reference = self.reference(record)?;
+                self.callstack.bind(alias, reference);
+                let res = self.visit_node(body);
+                self.callstack.unbind(alias);
+                res
+            }
             AST::ProcedureCall {
                 id,
                 params,
                 ast_params,
                 body,
                 scope_level,
-                ..
+                token,
             } => {
-                let mut ar = ActiveRecord::new(id.clone(), ARType::PROCEDURE, scope_level + 1);
-                for (formal, actual) in params.iter().zip(ast_params) {
-                    let res = self.visit_node(actual)?;
-                    ar.insert(formal.id.clone(), res.unwrap());
-                }
-                self.callstack.push(ar);
-                self.log(format!("ENTER: PROCEDURE {}", id));
-                self.log(format!("{}", self.callstack));
-
-                self.visit_node(*body.unwrap())?;
-
-                self.log(format!("LEAVE: PROCEDURE {}", id));
-                self.log(format!("{}", self.callstack));
-                self.callstack.pop();
+                self.check_depth(token)?;
+                let body = body.as_ref().and_then(RoutineBody::get).unwrap();
+                self.call(id, None, params, ast_params, body, *scope_level)?;
                 Ok(None)
             }
-            AST::NoOp | AST::ProcedureDecl { .. } => Ok(None),
+            AST::FunctionCall {
+                id,
+                params,
+                ast_params,
+                return_type,
+                body,
+                scope_level,
+                token,
+            } => {
+                self.check_depth(token)?;
+                let body = body.as_ref().and_then(RoutineBody::get).unwrap();
+                let return_type = return_type.as_ref();
+                let ar = self.call(id, return_type, params, ast_params, body, *scope_level)?;
+                match ar.return_value() {
+                    Some(value) => Ok(Some(value.clone())),
+                    None => Err(Error::UNDEFINED_RESULT(token.clone()).into()),
+                }
+            }
+            AST::Labeled {
+                label, statement, ..
+            } => loop {
+                // a goto inside of the statement to its own label runs it again
+                match self.visit_node(statement) {
+                    Err(Interrupt::GOTO(target)) if target == *label => {}
+                    res => break res,
+                }
+            },
+            AST::Goto { label, .. } => Err(Interrupt::GOTO(label.clone())),
+            AST::NoOp | AST::ProcedureDecl { .. } | AST::FunctionDecl { .. } => Ok(None),
         }
     }
 }
@@ -1,6 +1,5 @@
 use crate::errors::Error;
 use crate::token;
-use crate::var_type::VarType;
 use std::collections::HashMap;
 
 pub struct Lexer {
@@ -10,6 +9,7 @@ pub struct Lexer {
     current_char: Option<char>,
     lineno: usize,
     column: usize,
+    io_checks: bool,
 }
 
 impl Lexer {
@@ -22,18 +22,47 @@ impl Lexer {
             reserved_keywords: [
                 ("PROGRAM", token::Kind::PROGRAM),
                 ("VAR", token::Kind::VAR),
+                ("CONST", token::Kind::CONST),
                 ("DIV", token::Kind::INTEGER_DIV),
-                ("INTEGER", token::Kind::TYPE(VarType::INTEGER)),
-                ("REAL", token::Kind::TYPE(VarType::REAL)),
+                ("MOD", token::Kind::MOD),
+                ("AND", token::Kind::AND),
+                ("OR", token::Kind::OR),
+                ("NOT", token::Kind::NOT),
+                ("XOR", token::Kind::XOR),
+                ("SHL", token::Kind::SHL),
+                ("SHR", token::Kind::SHR),
+                ("TYPE", token::Kind::TYPE),
                 ("BEGIN", token::Kind::BEGIN),
                 ("END", token::Kind::END),
                 ("PROCEDURE", token::Kind::PROCEDURE),
+                ("FUNCTION", token::Kind::FUNCTION),
+                ("IF", token::Kind::IF),
+                ("THEN", token::Kind::THEN),
+                ("ELSE", token::Kind::ELSE),
+                ("WHILE", token::Kind::WHILE),
+                ("DO", token::Kind::DO),
+                ("REPEAT", token::Kind::REPEAT),
+                ("UNTIL", token::Kind::UNTIL),
+                ("FOR", token::Kind::FOR),
+                ("TO", token::Kind::TO),
+                ("DOWNTO", token::Kind::DOWNTO),
+                ("CASE", token::Kind::CASE),
+                ("OF", token::Kind::OF),
+                ("ARRAY", token::Kind::ARRAY),
+                ("RECORD", token::Kind::RECORD),
+                ("WITH", token::Kind::WITH),
+                ("NIL", token::Kind::NIL),
+                ("SET", token::Kind::SET),
+                ("IN", token::Kind::IN),
+                ("LABEL", token::Kind::LABEL),
+                ("GOTO", token::Kind::GOTO),
             ]
             .iter()
             .cloned()
             .collect(),
             lineno: 1,
             column: 0,
+            io_checks: true,
         };
         Ok(lex)
     }
@@ -80,13 +109,26 @@ impl Lexer {
         }
     }
 
+    // compiler directives are comments starting with $, only {$I+} and {$I-} are known
+    fn directive(&mut self) {
+        self.advance();
+        if matches!(self.current_char, Some('I' | 'i')) {
+            match self.peek() {
+                Some('+') => self.io_checks = true,
+                Some('-') => self.io_checks = false,
+                _ => {}
+            }
+        }
+    }
+
     fn number(&mut self) -> token::Token {
         let mut result = String::new();
         let mut is_real = false;
         let mut token = self.new_token(token::Kind::INTEGER_CONST(0));
         while let Some(current_char) = self.current_char {
             if current_char == '.' {
-                if is_real {
+                // a second dot means a range like 1..9
+                if is_real || self.peek() == Some('.') {
                     break;
                 }
                 is_real = true;
@@ -104,6 +146,35 @@ impl Lexer {
         token
     }
 
+    fn string(&mut self) -> Result<token::Token, Error> {
+        let mut result = String::new();
+        let mut token = self.new_token(token::Kind::STRING_CONST(String::new()));
+        self.advance();
+        loop {
+            match self.current_char {
+                Some('\'') if self.peek() == Some('\'') => {
+                    // a doubled quote stands for the quote itself
+                    result.push('\'');
+                    self.advance();
+                }
+                Some('\'') => break,
+                Some('\n') | None => {
+                    token.kind = token::Kind::STRING_CONST(result);
+                    return Err(Error::UNTERMINATED_STRING(token));
+                }
+                Some(current_char) => result.push(current_char),
+            }
+            self.advance();
+        }
+        self.advance();
+        let mut chars = result.chars();
+        token.kind = match (chars.next(), chars.next()) {
+            (Some(ch), None) => token::Kind::CHAR_CONST(ch),
+            _ => token::Kind::STRING_CONST(result),
+        };
+        Ok(token)
+    }
+
     fn id(&mut self) -> Result<token::Token, Error> {
         let mut result = String::new();
         let mut token = self.new_token(token::Kind::ID(String::new()));
@@ -128,6 +199,7 @@ impl Lexer {
             kind,
             lineno: self.lineno,
             column: self.column,
+            io_checks: self.io_checks,
         }
     }
 
@@ -135,6 +207,9 @@ impl Lexer {
         while let Some(current_char) = self.current_char {
             if current_char == '{' {
                 self.advance();
+                if self.current_char == Some('$') {
+                    self.directive();
+                }
                 self.skip_comment();
                 continue;
             }
@@ -151,6 +226,10 @@ impl Lexer {
                 return Ok(self.number());
             }
 
+            if current_char == '\'' {
+                return self.string();
+            }
+
             if current_char == ':' && self.peek().is_some() && self.peek().unwrap() == '=' {
                 let token = self.new_token(token::Kind::ASSIGN);
                 self.advance();
@@ -158,6 +237,34 @@ impl Lexer {
                 return Ok(token);
             }
 
+            if current_char == '.' && self.peek() == Some('.') {
+                let token = self.new_token(token::Kind::RANGE);
+                self.advance();
+                self.advance();
+                return Ok(token);
+            }
+
+            if current_char == '<' && self.peek() == Some('=') {
+                let token = self.new_token(token::Kind::LESS_EQUAL);
+                self.advance();
+                self.advance();
+                return Ok(token);
+            }
+
+            if current_char == '<' && self.peek() == Some('>') {
+                let token = self.new_token(token::Kind::NOT_EQUAL);
+                self.advance();
+                self.advance();
+                return Ok(token);
+            }
+
+            if current_char == '>' && self.peek() == Some('=') {
+                let token = self.new_token(token::Kind::GREATER_EQUAL);
+                self.advance();
+                self.advance();
+                return Ok(token);
+            }
+
             let kind = match current_char {
                 ';' => token::Kind::SEMI,
                 '*' => token::Kind::MUL,
@@ -168,7 +275,13 @@ impl Lexer {
                 ':' => token::Kind::COLON,
                 '(' => token::Kind::LPAREN,
                 ')' => token::Kind::RPAREN,
+                '[' => token::Kind::LBRACKET,
+                '^' => token::Kind::CARET,
+                ']' => token::Kind::RBRACKET,
                 '.' => token::Kind::DOT,
+                '=' => token::Kind::EQUAL,
+                '<' => token::Kind::LESS,
+                '>' => token::Kind::GREATER,
                 current_char => return Err(Error::INVALID_CHARACTER(current_char)),
             };
             let token = self.new_token(kind);
@@ -177,8 +290,4 @@ impl Lexer {
         }
         Ok(self.new_token(token::Kind::EOF))
     }
-
-    pub fn current_char(&self) -> Option<char> {
-        self.current_char
-    }
 }
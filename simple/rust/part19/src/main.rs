@@ -1,14 +1,20 @@
+#![allow(clippy::upper_case_acronyms)]
+
 use errors::Error;
-use interpreter::Interpreteter;
+use interpreter::{Interpreteter, STACK_SIZE};
 use lexer::Lexer;
 use opts::Opts;
 use parser::Parser;
 use semantic::SemanticAnalyzer;
 
 mod ast;
+mod builtins;
 mod calc;
 mod callstack;
 mod errors;
+mod files;
+mod heap;
+mod input;
 mod interpreter;
 mod lexer;
 mod opts;
@@ -18,7 +24,7 @@ mod symbols;
 mod token;
 mod var_type;
 
-fn execute() -> Result<(), Error> {
+fn execute() -> Result<i32, Error> {
     let opts = Opts::from_args();
     let content = std::fs::read_to_string(opts.filename).unwrap();
     let lexer = Lexer::new(&content)?;
@@ -27,13 +33,22 @@ fn execute() -> Result<(), Error> {
     let mut semantic_analyzer = SemanticAnalyzer::new(opts.debug_scope);
     semantic_analyzer.visit_node(&mut tree)?;
     let interpreter = Interpreteter::new(opts.debug_stack);
-    interpreter.interpret(tree)?;
-    Ok(())
+    interpreter.interpret(&tree)
 }
 
 fn main() {
-    if let Err(err) = execute() {
-        eprintln!("{}", err);
-        std::process::exit(1);
+    let result = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(execute)
+        .unwrap()
+        .join();
+    match result {
+        Ok(Ok(code)) => std::process::exit(code),
+        Ok(Err(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        // the panic has been reported by the thread
+        Err(_) => std::process::exit(101),
     }
 }
@@ -1,8 +1,7 @@
-use crate::ast::{Param, AST};
+use crate::ast::{CaseBranch, CaseLabel, Param, ParamMode, TypeSpec, AST};
 use crate::errors::Error;
 use crate::lexer::Lexer;
 use crate::token;
-use crate::var_type::VarType;
 
 pub struct Parser {
     lexer: Lexer,
@@ -16,6 +15,7 @@ impl Parser {
                 kind: token::Kind::EOF,
                 column: 0,
                 lineno: 0,
+                io_checks: true,
             },
             lexer,
         };
@@ -38,10 +38,11 @@ impl Parser {
     fn program(&mut self) -> Result<AST, Error> {
         // program : compound_statement DOT
         self.eat(token::Kind::PROGRAM)?;
-        let program_name = match self.variable()? {
-            AST::Var { id, .. } => id,
-            _ => panic!("impossible"),
+        let program_name = match self.current_token.kind.clone() {
+            token::Kind::ID(id) => id,
+            _ => String::new(),
         };
+        self.eat(token::Kind::ID(String::new()))?;
         self.eat(token::Kind::SEMI)?;
         let block_node = self.block()?;
         let program_node = AST::Program {
@@ -64,44 +65,90 @@ impl Parser {
     }
 
     fn declarations(&mut self) -> Result<Vec<AST>, Error> {
-        // declarations : VAR (variable_declaration SEMI)+
-        //             | (PROCEDURE ID SEMI block SEMI)*
-        //             | empty
+        // declarations : (LABEL label (COMMA label)* SEMI
+        //               | CONST (constant_declaration SEMI)+
+        //               | TYPE (type_declaration SEMI)+
+        //               | VAR (variable_declaration SEMI)+
+        //               | procedure_declaration
+        //               | function_declaration)*
         let mut declarations: Vec<AST> = Vec::new();
 
-        if self.current_token.kind == token::Kind::VAR {
-            self.eat(token::Kind::VAR)?;
-            while let token::Kind::ID(_) = self.current_token.kind {
-                let mut var_decl = self.variable_declaration()?;
-                declarations.append(&mut var_decl);
-                self.eat(token::Kind::SEMI)?;
+        loop {
+            match self.current_token.kind {
+                token::Kind::LABEL => {
+                    self.eat(token::Kind::LABEL)?;
+                    loop {
+                        let token = self.current_token.clone();
+                        let label = self.label()?;
+                        declarations.push(AST::LabelDecl { label, token });
+                        if self.current_token.kind != token::Kind::COMMA {
+                            break;
+                        }
+                        self.eat(token::Kind::COMMA)?;
+                    }
+                    self.eat(token::Kind::SEMI)?;
+                }
+                token::Kind::CONST => {
+                    self.eat(token::Kind::CONST)?;
+                    while let token::Kind::ID(_) = self.current_token.kind {
+                        declarations.push(self.constant_declaration()?);
+                        self.eat(token::Kind::SEMI)?;
+                    }
+                }
+                token::Kind::TYPE => {
+                    self.eat(token::Kind::TYPE)?;
+                    while let token::Kind::ID(_) = self.current_token.kind {
+                        declarations.push(self.type_declaration()?);
+                        self.eat(token::Kind::SEMI)?;
+                    }
+                }
+                token::Kind::VAR => {
+                    self.eat(token::Kind::VAR)?;
+                    while let token::Kind::ID(_) = self.current_token.kind {
+                        let mut var_decl = self.variable_declaration()?;
+                        declarations.append(&mut var_decl);
+                        self.eat(token::Kind::SEMI)?;
+                    }
+                }
+                token::Kind::PROCEDURE => declarations.push(self.procedure_declaration()?),
+                token::Kind::FUNCTION => declarations.push(self.function_declaration()?),
+                _ => break,
             }
         }
-
-        while let token::Kind::PROCEDURE = self.current_token.kind {
-            let proc_decl = self.procedure_declaration()?;
-            declarations.push(proc_decl);
-        }
         Ok(declarations)
     }
 
     fn formal_parameters(&mut self) -> Result<Vec<Param>, Error> {
-        // formal_parameters : ID (COMMA ID)* COLON type_spec
+        // formal_parameters : (VAR | CONST | OUT)? ID (COMMA ID)* COLON type_spec
         let mut ids: Vec<String> = Vec::new();
-        loop {
+        let mode = match self.current_token.kind.clone() {
+            token::Kind::VAR => ParamMode::VAR,
+            token::Kind::CONST => ParamMode::CONST,
+            // out is not a reserved word, it is the mode only when a name follows it
+            token::Kind::ID(id) if id.eq_ignore_ascii_case("OUT") => {
+                self.eat(token::Kind::ID(String::new()))?;
+                if let token::Kind::ID(_) = self.current_token.kind {
+                    ParamMode::OUT
+                } else {
+                    ids.push(id);
+                    ParamMode::VALUE
+                }
+            }
+            _ => ParamMode::VALUE,
+        };
+        if let ParamMode::VAR | ParamMode::CONST = mode {
+            self.eat(self.current_token.kind.clone())?;
+        }
+        while ids.is_empty() || self.current_token.kind == token::Kind::COMMA {
+            if !ids.is_empty() {
+                self.eat(token::Kind::COMMA)?;
+            }
             let id = match self.current_token.kind.clone() {
                 token::Kind::ID(id) => id,
                 _ => String::new(),
             };
             self.eat(token::Kind::ID(id.clone()))?;
             ids.push(id);
-            match self.current_token.kind.clone() {
-                kind @ token::Kind::COMMA => {
-                    self.eat(kind)?;
-                    continue;
-                }
-                _ => break,
-            };
         }
         self.eat(token::Kind::COLON)?;
         let ttype = self.type_spec()?;
@@ -110,6 +157,7 @@ impl Parser {
             .map(|id| Param {
                 id,
                 ttype: ttype.clone(),
+                mode: mode.clone(),
             })
             .collect();
         Ok(result)
@@ -118,8 +166,10 @@ impl Parser {
     fn formal_parameter_list(&mut self) -> Result<Vec<Param>, Error> {
         // formal_parameter_list : formal_parameters
         //                           | formal_parameters SEMI formal_parameter_list
-        if let token::Kind::ID(_) = self.current_token.kind.clone() {
-        } else {
+        if !matches!(
+            self.current_token.kind,
+            token::Kind::ID(_) | token::Kind::VAR | token::Kind::CONST
+        ) {
             return Ok(Vec::new());
         }
 
@@ -134,42 +184,70 @@ impl Parser {
         Ok(params)
     }
 
+    fn constant_declaration(&mut self) -> Result<AST, Error> {
+        // constant_declaration : ID EQUAL expr
+        let token = self.current_token.clone();
+        let id = match self.current_token.kind.clone() {
+            token::Kind::ID(id) => id,
+            _ => String::new(),
+        };
+        self.eat(token::Kind::ID(String::new()))?;
+        self.eat(token::Kind::EQUAL)?;
+        Ok(AST::ConstDecl {
+            id,
+            value: Box::new(self.expr()?),
+            token,
+        })
+    }
+
+    fn type_declaration(&mut self) -> Result<AST, Error> {
+        // type_declaration : ID EQUAL type_spec
+        let token = self.current_token.clone();
+        let id = match self.current_token.kind.clone() {
+            token::Kind::ID(id) => id,
+            _ => String::new(),
+        };
+        self.eat(token::Kind::ID(String::new()))?;
+        self.eat(token::Kind::EQUAL)?;
+        Ok(AST::TypeDecl {
+            id,
+            type_spec: self.type_spec()?,
+            token,
+        })
+    }
+
     fn variable_declaration(&mut self) -> Result<Vec<AST>, Error> {
         // variable_declaration : ID (COMMA ID)* COLON type_spec
-        let mut var_ids: Vec<String> = Vec::new();
+        let mut var_ids: Vec<(String, token::Token)> = Vec::new();
         if let token::Kind::ID(id) = self.current_token.kind.clone() {
-            var_ids.push(id);
+            var_ids.push((id, self.current_token.clone()));
         }
-        self.eat(self.current_token.kind.clone())?;
+        self.eat(token::Kind::ID(String::new()))?;
 
-        loop {
-            match self.current_token.kind.clone() {
-                comma @ token::Kind::COMMA => {
-                    self.eat(comma)?;
-                    if let token::Kind::ID(id) = self.current_token.kind.clone() {
-                        var_ids.push(id);
-                    }
-                    self.eat(self.current_token.kind.clone())?;
-                }
-                _ => break,
+        while let comma @ token::Kind::COMMA = self.current_token.kind.clone() {
+            self.eat(comma)?;
+            if let token::Kind::ID(id) = self.current_token.kind.clone() {
+                var_ids.push((id, self.current_token.clone()));
             }
+            self.eat(token::Kind::ID(String::new()))?;
         }
 
         self.eat(token::Kind::COLON)?;
         let var_type = self.type_spec()?;
         Ok(var_ids
             .into_iter()
-            .map(|id| AST::VarDecl {
+            .map(|(id, token)| AST::VarDecl {
                 id,
                 var_type: var_type.clone(),
-                token: self.current_token.clone(),
+                token,
             })
             .collect())
     }
 
     fn procedure_declaration(&mut self) -> Result<AST, Error> {
-        // PROCEDURE ID (LPAREN formal_parameter_list RPAREN)? SEMI block SEMI
+        // PROCEDURE ID (LPAREN formal_parameter_list RPAREN)? SEMI routine_body SEMI
         self.eat(token::Kind::PROCEDURE)?;
+        let token = self.current_token.clone();
         let id = match self.current_token.kind.clone() {
             token::Kind::ID(id) => id,
             _ => String::new(),
@@ -187,19 +265,156 @@ impl Parser {
         let proc_decl = AST::ProcedureDecl {
             id,
             params,
-            block_node: Box::new(self.block()?),
+            block_node: self.routine_body()?,
+            token,
         };
         self.eat(token::Kind::SEMI)?;
         Ok(proc_decl)
     }
 
-    fn type_spec(&mut self) -> Result<VarType, Error> {
-        let var_type = match self.current_token.kind.clone() {
-            token::Kind::TYPE(var_type) => var_type,
-            _ => VarType::INTEGER,
+    fn function_declaration(&mut self) -> Result<AST, Error> {
+        // FUNCTION ID (LPAREN formal_parameter_list RPAREN)? (COLON type_spec)? SEMI
+        // routine_body SEMI
+        self.eat(token::Kind::FUNCTION)?;
+        let token = self.current_token.clone();
+        let id = match self.current_token.kind.clone() {
+            token::Kind::ID(id) => id,
+            _ => String::new(),
+        };
+        self.eat(token::Kind::ID(String::new()))?;
+        let mut params = Vec::new();
+
+        if let token::Kind::LPAREN = self.current_token.kind {
+            self.eat(token::Kind::LPAREN)?;
+            params = self.formal_parameter_list()?;
+            self.eat(token::Kind::RPAREN)?;
+        }
+
+        let mut return_type = None;
+        if let token::Kind::COLON = self.current_token.kind {
+            self.eat(token::Kind::COLON)?;
+            return_type = Some(self.type_spec()?);
+        }
+        self.eat(token::Kind::SEMI)?;
+        let func_decl = AST::FunctionDecl {
+            id,
+            params,
+            return_type,
+            block_node: self.routine_body()?,
+            token,
         };
-        self.eat(token::Kind::TYPE(var_type.clone()))?;
-        Ok(var_type)
+        self.eat(token::Kind::SEMI)?;
+        Ok(func_decl)
+    }
+
+    fn routine_body(&mut self) -> Result<Option<Box<AST>>, Error> {
+        // routine_body : block | FORWARD
+        // forward is a directive, so it is not a reserved word
+        match &self.current_token.kind {
+            token::Kind::ID(id) if id.eq_ignore_ascii_case("FORWARD") => {
+                self.eat(token::Kind::ID(String::new()))?;
+                Ok(None)
+            }
+            _ => Ok(Some(Box::new(self.block()?))),
+        }
+    }
+
+    fn type_spec(&mut self) -> Result<TypeSpec, Error> {
+        // type_spec : ID
+        //           | LPAREN ID (COMMA ID)* RPAREN
+        //           | simple_expr RANGE simple_expr
+        //           | array_type
+        //           | record_type
+        //           | SET OF type_spec
+        //           | CARET ID
+        let token = self.current_token.clone();
+        match token.kind {
+            token::Kind::ARRAY => return self.array_type(),
+            token::Kind::RECORD => return self.record_type(),
+            token::Kind::SET => {
+                self.eat(token::Kind::SET)?;
+                self.eat(token::Kind::OF)?;
+                return Ok(TypeSpec::Set {
+                    element: Box::new(self.type_spec()?),
+                    token,
+                });
+            }
+            token::Kind::CARET => {
+                self.eat(token::Kind::CARET)?;
+                let id = match self.current_token.kind.clone() {
+                    token::Kind::ID(id) => id,
+                    _ => String::new(),
+                };
+                let token = self.current_token.clone();
+                self.eat(token::Kind::ID(String::new()))?;
+                return Ok(TypeSpec::Pointer { id, token });
+            }
+            _ => {}
+        }
+        if let token::Kind::LPAREN = token.kind {
+            self.eat(token::Kind::LPAREN)?;
+            let mut values = Vec::new();
+            loop {
+                if let token::Kind::ID(id) = self.current_token.kind.clone() {
+                    values.push(id);
+                }
+                self.eat(token::Kind::ID(String::new()))?;
+                match self.current_token.kind {
+                    token::Kind::COMMA => self.eat(token::Kind::COMMA)?,
+                    _ => break,
+                }
+            }
+            self.eat(token::Kind::RPAREN)?;
+            return Ok(TypeSpec::Enum { values, token });
+        }
+
+        let low = self.simple_expr()?;
+        if let token::Kind::RANGE = self.current_token.kind {
+            self.eat(token::Kind::RANGE)?;
+            return Ok(TypeSpec::Subrange {
+                low: Box::new(low),
+                high: Box::new(self.simple_expr()?),
+                token,
+            });
+        }
+        match low {
+            AST::Var { id, token, .. } => Ok(TypeSpec::Named { id, token }),
+            _ => Err(Error::UNEXPECTED_TOKEN(self.current_token.clone())),
+        }
+    }
+
+    fn array_type(&mut self) -> Result<TypeSpec, Error> {
+        // array_type : ARRAY LBRACKET type_spec (COMMA type_spec)* RBRACKET OF type_spec
+        let token = self.current_token.clone();
+        self.eat(token::Kind::ARRAY)?;
+        self.eat(token::Kind::LBRACKET)?;
+        let mut index = vec![self.type_spec()?];
+        while let token::Kind::COMMA = self.current_token.kind {
+            self.eat(token::Kind::COMMA)?;
+            index.push(self.type_spec()?);
+        }
+        self.eat(token::Kind::RBRACKET)?;
+        self.eat(token::Kind::OF)?;
+        Ok(TypeSpec::Array {
+            index,
+            element: Box::new(self.type_spec()?),
+            token,
+        })
+    }
+
+    fn record_type(&mut self) -> Result<TypeSpec, Error> {
+        // record_type : RECORD (variable_declaration (SEMI variable_declaration)* SEMI?)? END
+        self.eat(token::Kind::RECORD)?;
+        let mut fields = Vec::new();
+        while let token::Kind::ID(_) = self.current_token.kind {
+            fields.append(&mut self.variable_declaration()?);
+            match self.current_token.kind {
+                token::Kind::SEMI => self.eat(token::Kind::SEMI)?,
+                _ => break,
+            }
+        }
+        self.eat(token::Kind::END)?;
+        Ok(TypeSpec::Record { fields })
     }
 
     fn compound_statement(&mut self) -> Result<AST, Error> {
@@ -229,46 +444,263 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Result<AST, Error> {
-        // statement : compound_statement
+        // statement : label COLON statement
+        //           | compound_statement
+        //           | if_statement
+        //           | while_statement
+        //           | repeat_statement
+        //           | for_statement
+        //           | case_statement
+        //           | with_statement
+        //           | goto_statement
+        //           | proccall_statement
         //           | assignment_statement
         //           | empty
         let token = self.current_token.clone();
         let node;
         if let token::Kind::BEGIN = token.kind {
             node = self.compound_statement()?;
-        } else if std::mem::discriminant(&token.kind)
-            == std::mem::discriminant(&token::Kind::ID(String::new()))
-            && self.lexer.current_char().unwrap_or('0') == '('
-        {
-            node = self.proccall_statement()?;
-        } else if let token::Kind::ID(id) = token.kind {
-            node = self.assignment_statement(id)?;
+        } else if let token::Kind::IF = token.kind {
+            node = self.if_statement()?;
+        } else if let token::Kind::WHILE = token.kind {
+            node = self.while_statement()?;
+        } else if let token::Kind::REPEAT = token.kind {
+            node = self.repeat_statement()?;
+        } else if let token::Kind::FOR = token.kind {
+            node = self.for_statement()?;
+        } else if let token::Kind::CASE = token.kind {
+            node = self.case_statement()?;
+        } else if let token::Kind::WITH = token.kind {
+            node = self.with_statement()?;
+        } else if let token::Kind::GOTO = token.kind {
+            node = self.goto_statement()?;
+        } else if let token::Kind::INTEGER_CONST(_) = token.kind {
+            let label = self.label()?;
+            node = self.labeled_statement(label, token)?;
+        } else if let token::Kind::ID(_) = token.kind {
+            let var = self.variable()?;
+            node = match (self.current_token.kind.clone(), var) {
+                (token::Kind::COLON, AST::Var { id, .. }) => self.labeled_statement(id, token)?,
+                (token::Kind::ASSIGN, var) => self.assignment_statement(var)?,
+                (_, var) => self.proccall_statement(var)?,
+            };
         } else {
             node = AST::NoOp;
         }
         Ok(node)
     }
 
-    fn proccall_statement(&mut self) -> Result<AST, Error> {
-        // proccall_statement : ID LPAREN (expr (COMMA expr)*)? RPAREN
+    fn label(&mut self) -> Result<String, Error> {
+        // label : INTEGER_CONST | ID
+        let label = match &self.current_token.kind {
+            token::Kind::INTEGER_CONST(value) => value.to_string(),
+            token::Kind::ID(id) => id.clone(),
+            _ => return Err(Error::UNEXPECTED_TOKEN(self.current_token.clone())),
+        };
+        self.current_token = self.lexer.next_token()?;
+        Ok(label)
+    }
+
+    fn labeled_statement(&mut self, label: String, token: token::Token) -> Result<AST, Error> {
+        // labeled_statement : label COLON statement
+        // the label is already eaten
+        self.eat(token::Kind::COLON)?;
+        let statement = Box::new(self.statement()?);
+        Ok(AST::Labeled {
+            label,
+            statement,
+            token,
+        })
+    }
+
+    fn goto_statement(&mut self) -> Result<AST, Error> {
+        // goto_statement : GOTO label
         let token = self.current_token.clone();
-        let id = match self.current_token.kind.clone() {
+        self.eat(token::Kind::GOTO)?;
+        let label = self.label()?;
+        Ok(AST::Goto { label, token })
+    }
+
+    fn if_statement(&mut self) -> Result<AST, Error> {
+        // if_statement : IF expr THEN statement (ELSE statement)?
+        let token = self.current_token.clone();
+        self.eat(token::Kind::IF)?;
+        let condition = Box::new(self.expr()?);
+        self.eat(token::Kind::THEN)?;
+        let then_node = Box::new(self.statement()?);
+        let mut else_node = None;
+        if let token::Kind::ELSE = self.current_token.kind {
+            self.eat(token::Kind::ELSE)?;
+            else_node = Some(Box::new(self.statement()?));
+        }
+        Ok(AST::If {
+            condition,
+            then_node,
+            else_node,
+            token,
+        })
+    }
+
+    fn while_statement(&mut self) -> Result<AST, Error> {
+        // while_statement : WHILE expr DO statement
+        let token = self.current_token.clone();
+        self.eat(token::Kind::WHILE)?;
+        let condition = Box::new(self.expr()?);
+        self.eat(token::Kind::DO)?;
+        let body = Box::new(self.statement()?);
+        Ok(AST::While {
+            condition,
+            body,
+            token,
+        })
+    }
+
+    fn repeat_statement(&mut self) -> Result<AST, Error> {
+        // repeat_statement : REPEAT statement_list UNTIL expr
+        let token = self.current_token.clone();
+        self.eat(token::Kind::REPEAT)?;
+        let children = self.statement_list()?;
+        self.eat(token::Kind::UNTIL)?;
+        let condition = Box::new(self.expr()?);
+        Ok(AST::Repeat {
+            children,
+            condition,
+            token,
+        })
+    }
+
+    fn for_statement(&mut self) -> Result<AST, Error> {
+        // for_statement : FOR ID ASSIGN expr (TO | DOWNTO) expr DO statement
+        let token = self.current_token.clone();
+        self.eat(token::Kind::FOR)?;
+        let var_token = self.current_token.clone();
+        let var_id = match self.current_token.kind.clone() {
             token::Kind::ID(id) => id,
             _ => String::new(),
         };
         self.eat(token::Kind::ID(String::new()))?;
-        self.eat(token::Kind::LPAREN)?;
-        let mut ast_params = Vec::new();
+        let var = Box::new(AST::Var {
+            id: var_id.clone(),
+            token: var_token,
+            scope_level: 0,
+        });
+        self.eat(token::Kind::ASSIGN)?;
+        let start = Box::new(self.expr()?);
+        let downto = match self.current_token.kind {
+            token::Kind::DOWNTO => {
+                self.eat(token::Kind::DOWNTO)?;
+                true
+            }
+            _ => {
+                self.eat(token::Kind::TO)?;
+                false
+            }
+        };
+        let end = Box::new(self.expr()?);
+        self.eat(token::Kind::DO)?;
+        let body = Box::new(self.statement()?);
+        Ok(AST::For {
+            var_id,
+            var,
+            start,
+            end,
+            downto,
+            body,
+            token,
+        })
+    }
+
+    fn case_statement(&mut self) -> Result<AST, Error> {
+        // case_statement : CASE expr OF case_branch (SEMI case_branch)* SEMI?
+        //                  ((ELSE | OTHERWISE) statement_list)? END
+        let token = self.current_token.clone();
+        self.eat(token::Kind::CASE)?;
+        let selector = Box::new(self.expr()?);
+        self.eat(token::Kind::OF)?;
+        let mut branches = vec![self.case_branch()?];
+        while let token::Kind::SEMI = self.current_token.kind {
+            self.eat(token::Kind::SEMI)?;
+            match self.current_token.kind {
+                token::Kind::ELSE | token::Kind::END => break,
+                _ if self.at_otherwise() => break,
+                _ => branches.push(self.case_branch()?),
+            }
+        }
+        let mut else_nodes = None;
+        if self.current_token.kind == token::Kind::ELSE || self.at_otherwise() {
+            self.eat(self.current_token.kind.clone())?;
+            else_nodes = Some(self.statement_list()?);
+        }
+        self.eat(token::Kind::END)?;
+        Ok(AST::Case {
+            selector,
+            branches,
+            else_nodes,
+            token,
+        })
+    }
+
+    // otherwise is not a reserved word, it only starts the else part of a case
+    fn at_otherwise(&self) -> bool {
+        matches!(&self.current_token.kind, token::Kind::ID(id) if id.eq_ignore_ascii_case("OTHERWISE"))
+    }
+
+    fn case_branch(&mut self) -> Result<CaseBranch, Error> {
+        // case_branch : case_label (COMMA case_label)* COLON statement
+        let mut labels = Vec::new();
         loop {
-            let node = self.expr()?;
-            ast_params.push(node);
-            match self.current_token.kind.clone() {
-                kind @ token::Kind::COMMA => self.eat(kind)?,
+            // case_label : expr (RANGE expr)?
+            let token = self.current_token.clone();
+            let low = self.expr()?;
+            let mut high = None;
+            if let token::Kind::RANGE = self.current_token.kind {
+                self.eat(token::Kind::RANGE)?;
+                high = Some(self.expr()?);
+            }
+            labels.push(CaseLabel { low, high, token });
+            match self.current_token.kind {
+                token::Kind::COMMA => self.eat(token::Kind::COMMA)?,
                 _ => break,
-            };
+            }
         }
+        self.eat(token::Kind::COLON)?;
+        let body = self.statement()?;
+        Ok(CaseBranch { labels, body })
+    }
 
-        self.eat(token::Kind::RPAREN)?;
+    fn with_statement(&mut self) -> Result<AST, Error> {
+        // with_statement : WITH variable (COMMA variable)* DO statement
+        let token = self.current_token.clone();
+        self.eat(token::Kind::WITH)?;
+        let mut records = vec![self.variable()?];
+        while let token::Kind::COMMA = self.current_token.kind {
+            self.eat(token::Kind::COMMA)?;
+            records.push(self.variable()?);
+        }
+        self.eat(token::Kind::DO)?;
+        // several records are nested WITH statements
+        let body = self.statement()?;
+        Ok(records
+            .into_iter()
+            .rev()
+            .fold(body, |body, record| AST::With {
+                record: Box::new(record),
+                alias: String::new(),
+                body: Box::new(body),
+                token: token.clone(),
+            }))
+    }
+
+    fn proccall_statement(&mut self, var: AST) -> Result<AST, Error> {
+        // proccall_statement : ID actual_parameters?
+        let (id, token) = match var {
+            AST::Var { id, token, .. } => (id, token),
+            _ => return Err(Error::UNEXPECTED_TOKEN(self.current_token.clone())),
+        };
+        let mut ast_params = Vec::new();
+        if let token::Kind::LPAREN = self.current_token.kind {
+            ast_params = self.actual_parameters()?;
+        }
         Ok(AST::ProcedureCall {
             id,
             ast_params,
@@ -279,61 +711,199 @@ impl Parser {
         })
     }
 
-    fn assignment_statement(&mut self, left_id: String) -> Result<AST, Error> {
+    fn function_call(&mut self, var: AST) -> Result<AST, Error> {
+        // function_call : ID actual_parameters
+        let (id, token) = match var {
+            AST::Var { id, token, .. } => (id, token),
+            _ => return Err(Error::UNEXPECTED_TOKEN(self.current_token.clone())),
+        };
+        Ok(AST::FunctionCall {
+            id,
+            ast_params: self.actual_parameters()?,
+            params: Vec::new(),
+            return_type: None,
+            token,
+            body: None,
+            scope_level: 0,
+        })
+    }
+
+    fn actual_parameters(&mut self) -> Result<Vec<AST>, Error> {
+        // actual_parameters : LPAREN (actual_parameter (COMMA actual_parameter)*)? RPAREN
+        self.eat(token::Kind::LPAREN)?;
+        let mut ast_params = Vec::new();
+        if let token::Kind::RPAREN = self.current_token.kind {
+            self.eat(token::Kind::RPAREN)?;
+            return Ok(ast_params);
+        }
+        loop {
+            let node = self.actual_parameter()?;
+            ast_params.push(node);
+            match self.current_token.kind.clone() {
+                kind @ token::Kind::COMMA => self.eat(kind)?,
+                _ => break,
+            };
+        }
+
+        self.eat(token::Kind::RPAREN)?;
+        Ok(ast_params)
+    }
+
+    fn actual_parameter(&mut self) -> Result<AST, Error> {
+        // actual_parameter : expr (COLON expr (COLON expr)?)?
+        let expr = self.expr()?;
+        let token = self.current_token.clone();
+        if let token::Kind::COLON = token.kind {
+            self.eat(token::Kind::COLON)?;
+            let width = Box::new(self.expr()?);
+            let mut precision = None;
+            if let token::Kind::COLON = self.current_token.kind {
+                self.eat(token::Kind::COLON)?;
+                precision = Some(Box::new(self.expr()?));
+            }
+            return Ok(AST::Format {
+                expr: Box::new(expr),
+                width,
+                precision,
+                token,
+            });
+        }
+        Ok(expr)
+    }
+
+    fn assignment_statement(&mut self, left: AST) -> Result<AST, Error> {
         // assignment_statement : variable ASSIGN expr
-        let left = Box::new(self.variable()?);
+        let token = self.current_token.clone();
         self.eat(token::Kind::ASSIGN)?;
         let right = Box::new(self.expr()?);
         Ok(AST::Assign {
-            left_id,
-            left,
+            left: Box::new(left),
             right,
+            token,
         })
     }
 
     fn variable(&mut self) -> Result<AST, Error> {
-        // variable : ID
+        // variable : ID (LBRACKET expr (COMMA expr)* RBRACKET | DOT ID | CARET)*
         let id_token = self.current_token.clone();
         let id = match self.current_token.kind.clone() {
             token::Kind::ID(id) => id,
             _ => String::new(),
         };
         self.eat(token::Kind::ID(id.clone()))?;
-        Ok(AST::Var {
+        let mut node = AST::Var {
             id,
-            token: id_token,
-        })
+            token: id_token.clone(),
+            scope_level: 0,
+        };
+        loop {
+            match self.current_token.kind {
+                token::Kind::LBRACKET => {
+                    self.eat(token::Kind::LBRACKET)?;
+                    loop {
+                        node = AST::Index {
+                            array: Box::new(node),
+                            index: Box::new(self.expr()?),
+                            token: id_token.clone(),
+                        };
+                        match self.current_token.kind {
+                            token::Kind::COMMA => self.eat(token::Kind::COMMA)?,
+                            _ => break,
+                        }
+                    }
+                    self.eat(token::Kind::RBRACKET)?;
+                }
+                token::Kind::DOT => {
+                    self.eat(token::Kind::DOT)?;
+                    let token = self.current_token.clone();
+                    let field = match self.current_token.kind.clone() {
+                        token::Kind::ID(id) => id,
+                        _ => String::new(),
+                    };
+                    self.eat(token::Kind::ID(String::new()))?;
+                    node = AST::Field {
+                        record: Box::new(node),
+                        field,
+                        token,
+                    };
+                }
+                token::Kind::CARET => {
+                    let token = self.current_token.clone();
+                    self.eat(token::Kind::CARET)?;
+                    node = AST::Deref {
+                        pointer: Box::new(node),
+                        token,
+                    };
+                }
+                _ => break,
+            }
+        }
+        Ok(node)
     }
 
     fn expr(&mut self) -> Result<AST, Error> {
-        // expr : term ((PLUS | MINUS) term)*
+        // expr : simple_expr (relational_operator simple_expr)?
+        let left_node = self.simple_expr()?;
+        let token = self.current_token.clone();
+        match token.kind.clone() {
+            kind @ (token::Kind::EQUAL
+            | token::Kind::NOT_EQUAL
+            | token::Kind::LESS
+            | token::Kind::LESS_EQUAL
+            | token::Kind::GREATER
+            | token::Kind::GREATER_EQUAL
+            | token::Kind::IN) => {
+                self.eat(kind.clone())?;
+                Ok(AST::BinOp {
+                    left: Box::new(left_node),
+                    op: kind,
+                    right: Box::new(self.simple_expr()?),
+                    token,
+                })
+            }
+            _ => Ok(left_node),
+        }
+    }
+
+    fn simple_expr(&mut self) -> Result<AST, Error> {
+        // simple_expr : term ((PLUS | MINUS | OR | XOR) term)*
         let mut left_node = self.term()?;
 
         loop {
             let token = self.current_token.clone();
             match token.kind.clone() {
-                kind @ (token::Kind::PLUS | token::Kind::MINUS) => {
+                kind @ (token::Kind::PLUS
+                | token::Kind::MINUS
+                | token::Kind::OR
+                | token::Kind::XOR) => {
                     self.eat(kind)?;
                 }
                 _ => break,
             }
             left_node = AST::BinOp {
                 left: Box::new(left_node),
-                op: token.kind,
+                op: token.kind.clone(),
                 right: Box::new(self.term()?),
+                token,
             };
         }
         Ok(left_node)
     }
 
     fn term(&mut self) -> Result<AST, Error> {
-        // term : factor ((MUL | INTEGER_DIV | FLOAT_DIV) factor)*
+        // term : factor ((MUL | INTEGER_DIV | FLOAT_DIV | MOD | AND | SHL | SHR) factor)*
         let mut left_node = self.factor()?;
 
         loop {
             let token = self.current_token.clone();
             match token.kind.clone() {
-                kind @ (token::Kind::MUL | token::Kind::INTEGER_DIV | token::Kind::FLOAT_DIV) => {
+                kind @ (token::Kind::MUL
+                | token::Kind::INTEGER_DIV
+                | token::Kind::FLOAT_DIV
+                | token::Kind::MOD
+                | token::Kind::AND
+                | token::Kind::SHL
+                | token::Kind::SHR) => {
                     self.eat(kind)?;
                 }
                 _ => {
@@ -342,8 +912,9 @@ impl Parser {
             };
             left_node = AST::BinOp {
                 left: Box::new(left_node),
-                op: token.kind,
+                op: token.kind.clone(),
                 right: Box::new(self.factor()?),
+                token,
             };
         }
         Ok(left_node)
@@ -352,16 +923,25 @@ impl Parser {
     fn factor(&mut self) -> Result<AST, Error> {
         // factor : PLUS factor
         //           | MINUS factor
+        //           | NOT factor
         //           | INTEGER
+        //           | REAL_CONST
+        //           | CHAR_CONST
+        //           | STRING_CONST
+        //           | NIL
+        //           | set_constructor
         //           | LPAREN expr RPAREN
+        //           | function_call
         //           | variable
         let kind = self.current_token.kind.clone();
         match kind {
-            token::Kind::PLUS | token::Kind::MINUS => {
+            token::Kind::PLUS | token::Kind::MINUS | token::Kind::NOT => {
+                let token = self.current_token.clone();
                 self.eat(kind.clone())?;
                 Ok(AST::UnaryOp {
                     op: kind,
                     expr: Box::new(self.factor()?),
+                    token,
                 })
             }
             token::Kind::INTEGER_CONST(value) => {
@@ -372,48 +952,145 @@ impl Parser {
                 self.eat(kind)?;
                 Ok(AST::NumReal { value })
             }
+            token::Kind::CHAR_CONST(value) => {
+                self.eat(kind)?;
+                Ok(AST::Char { value })
+            }
+            token::Kind::STRING_CONST(ref value) => {
+                let value = value.clone();
+                self.eat(kind)?;
+                Ok(AST::Str { value })
+            }
+            token::Kind::NIL => {
+                self.eat(kind)?;
+                Ok(AST::Nil)
+            }
+            token::Kind::LBRACKET => self.set_constructor(),
             token::Kind::LPAREN => {
                 self.eat(kind)?;
                 let node = self.expr()?;
                 self.eat(token::Kind::RPAREN)?;
                 Ok(node)
             }
-            token::Kind::ID(_) => self.variable(),
+            token::Kind::ID(_) => {
+                let var = self.variable()?;
+                match self.current_token.kind {
+                    token::Kind::LPAREN => self.function_call(var),
+                    _ => Ok(var),
+                }
+            }
             _ => Err(Error::UNEXPECTED_TOKEN(self.current_token.clone())),
         }
     }
 
+    fn set_constructor(&mut self) -> Result<AST, Error> {
+        // set_constructor : LBRACKET (set_element (COMMA set_element)*)? RBRACKET
+        let token = self.current_token.clone();
+        self.eat(token::Kind::LBRACKET)?;
+        let mut elements = Vec::new();
+        if self.current_token.kind != token::Kind::RBRACKET {
+            loop {
+                // set_element : expr (RANGE expr)?
+                let low = self.expr()?;
+                let mut high = None;
+                if let token::Kind::RANGE = self.current_token.kind {
+                    self.eat(token::Kind::RANGE)?;
+                    high = Some(self.expr()?);
+                }
+                elements.push((low, high));
+                match self.current_token.kind {
+                    token::Kind::COMMA => self.eat(token::Kind::COMMA)?,
+                    _ => break,
+                }
+            }
+        }
+        self.eat(token::Kind::RBRACKET)?;
+        Ok(AST::Set { elements, token })
+    }
+
     pub fn parse(mut self) -> Result<AST, Error> {
         /*
         program : PROGRAM variable SEMI block DOT
         block : declarations compound_statement
-        declarations : (VAR (variable_declaration SEMI)+)? procedure_declaration*
+        declarations : (LABEL label (COMMA label)* SEMI
+                       | CONST (constant_declaration SEMI)+
+                       | TYPE (type_declaration SEMI)+
+                       | VAR (variable_declaration SEMI)+
+                       | procedure_declaration
+                       | function_declaration)*
+        constant_declaration : ID EQUAL expr
+        type_declaration : ID EQUAL type_spec
         variable_declaration : ID (COMMA ID)* COLON type_spec
         procedure_declaration :
-             PROCEDURE ID (LPAREN formal_parameter_list RPAREN)? SEMI block SEMI
+             PROCEDURE ID (LPAREN formal_parameter_list RPAREN)? SEMI routine_body SEMI
+        function_declaration :
+             FUNCTION ID (LPAREN formal_parameter_list RPAREN)? (COLON type_spec)? SEMI
+             routine_body SEMI
+        routine_body : block | FORWARD
         formal_params_list : formal_parameters
                            | formal_parameters SEMI formal_parameter_list
-        formal_parameters : ID (COMMA ID)* COLON type_spec
-        type_spec : INTEGER | REAL
+        formal_parameters : (VAR | CONST | OUT)? ID (COMMA ID)* COLON type_spec
+        type_spec : ID
+                  | LPAREN ID (COMMA ID)* RPAREN
+                  | simple_expr RANGE simple_expr
+                  | array_type
+                  | record_type
+                  | SET OF type_spec
+                  | CARET ID
+        array_type : ARRAY LBRACKET type_spec (COMMA type_spec)* RBRACKET OF type_spec
+        record_type : RECORD (variable_declaration (SEMI variable_declaration)* SEMI?)? END
         compound_statement : BEGIN statement_list END
         statement_list : statement
                        | statement SEMI statement_list
-        statement : compound_statement
+        statement : label COLON statement
+                  | compound_statement
+                  | if_statement
+                  | while_statement
+                  | repeat_statement
+                  | for_statement
+                  | case_statement
+                  | with_statement
+                  | goto_statement
                   | proccall_statement
                   | assignment_statement
                   | empty
-        proccall_statement : ID LPAREN (expr (COMMA expr)*)? RPAREN
+        if_statement : IF expr THEN statement (ELSE statement)?
+        while_statement : WHILE expr DO statement
+        repeat_statement : REPEAT statement_list UNTIL expr
+        for_statement : FOR ID ASSIGN expr (TO | DOWNTO) expr DO statement
+        case_statement : CASE expr OF case_branch (SEMI case_branch)* SEMI?
+                         ((ELSE | OTHERWISE) statement_list)? END
+        case_branch : case_label (COMMA case_label)* COLON statement
+        case_label : expr (RANGE expr)?
+        with_statement : WITH variable (COMMA variable)* DO statement
+        goto_statement : GOTO label
+        label : INTEGER_CONST | ID
+        proccall_statement : ID actual_parameters?
+        actual_parameters : LPAREN (actual_parameter (COMMA actual_parameter)*)? RPAREN
+        actual_parameter : expr (COLON expr (COLON expr)?)?
         assignment_statement : variable ASSIGN expr
         empty :
-        expr : term ((PLUS | MINUS) term)*
-        term : factor ((MUL | INTEGER_DIV | FLOAT_DIV) factor)*
+        expr : simple_expr (relational_operator simple_expr)?
+        relational_operator : EQUAL | NOT_EQUAL | LESS | LESS_EQUAL
+                            | GREATER | GREATER_EQUAL | IN
+        simple_expr : term ((PLUS | MINUS | OR | XOR) term)*
+        term : factor ((MUL | INTEGER_DIV | FLOAT_DIV | MOD | AND | SHL | SHR) factor)*
         factor : PLUS factor
                | MINUS factor
+               | NOT factor
                | INTEGER_CONST
                | REAL_CONST
+               | CHAR_CONST
+               | STRING_CONST
+               | NIL
+               | set_constructor
                | LPAREN expr RPAREN
+               | function_call
                | variable
-        variable: ID
+        set_constructor : LBRACKET (set_element (COMMA set_element)*)? RBRACKET
+        set_element : expr (RANGE expr)?
+        function_call : ID actual_parameters
+        variable : ID (LBRACKET expr (COMMA expr)* RBRACKET | DOT ID | CARET)*
         */
         let node = self.program()?;
         if std::mem::discriminant(&self.current_token.kind)
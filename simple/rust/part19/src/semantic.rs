@@ -1,11 +1,198 @@
-use crate::ast::AST;
+use crate::ast::{Param, ParamMode, RoutineBody, TypeSpec, AST};
+use crate::builtins::{bound, evaluate, BuiltIn};
+use crate::calc::{binary_operation, set_value, unary_operation, CalcResult, SET_SIZE};
+use crate::callstack::RETURN_VALUE;
 use crate::errors::Error;
 use crate::symbols::ScopedSymbolTable;
 use crate::symbols::Symbol;
+use crate::token;
+use crate::var_type::VarType;
+
+fn literal(value: &CalcResult, var_type: &VarType) -> AST {
+    match value {
+        CalcResult::INTEGER(value) => AST::NumInteger { value: *value },
+        CalcResult::FLOAT(value) => AST::NumReal { value: *value },
+        CalcResult::BOOLEAN(value) => AST::Boolean { value: *value },
+        CalcResult::CHAR(value) => AST::Char { value: *value },
+        CalcResult::STRING(value) => AST::Str {
+            value: value.clone(),
+        },
+        value => AST::Constant {
+            value: value.clone(),
+            var_type: var_type.clone(),
+        },
+    }
+}
+
+// evaluates an expression which is known at compile time
+fn constant_value(node: &AST) -> Option<CalcResult> {
+    match node {
+        AST::NumInteger { value } => Some(CalcResult::INTEGER(*value)),
+        AST::NumReal { value } => Some(CalcResult::FLOAT(*value)),
+        AST::Boolean { value } => Some(CalcResult::BOOLEAN(*value)),
+        AST::Char { value } => Some(CalcResult::CHAR(*value)),
+        AST::Str { value } => Some(CalcResult::STRING(value.clone())),
+        AST::Constant { value, .. } => Some(value.clone()),
+        AST::Nil => Some(CalcResult::POINTER(None)),
+        AST::Convert { expr, var_type } => Some(constant_value(expr)?.convert(var_type)),
+        AST::UnaryOp { op, expr, .. } => Some(unary_operation(op, constant_value(expr)?)),
+        AST::BinOp {
+            left, right, op, ..
+        } => binary_operation(op, constant_value(left)?, constant_value(right)?),
+        AST::BuiltInCall { builtin, args, .. } => {
+            let args = args
+                .iter()
+                .map(constant_value)
+                .collect::<Option<Vec<_>>>()?;
+            evaluate(*builtin, &args)
+        }
+        AST::Set { elements, .. } => {
+            let ranges = elements
+                .iter()
+                .map(|(low, high)| {
+                    let low = constant_value(low)?.as_ordinal();
+                    let high = match high {
+                        Some(high) => constant_value(high)?.as_ordinal(),
+                        None => low,
+                    };
+                    Some((low, high))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            set_value(&ranges)
+        }
+        _ => None,
+    }
+}
+
+// checks whether the parameters of a routine are the same as in its forward declaration
+fn same_parameters(forward: &[Param], params: &[Param]) -> bool {
+    forward.len() == params.len()
+        && forward.iter().zip(params).all(|(forward, param)| {
+            forward.id.eq_ignore_ascii_case(&param.id)
+                && forward.mode == param.mode
+                && forward.ttype.var_type() == param.ttype.var_type()
+        })
+}
+
+// checks whether the node is something a value can be stored to
+fn is_variable(node: &AST) -> bool {
+    matches!(
+        node,
+        AST::Var { .. } | AST::Index { .. } | AST::Field { .. } | AST::Deref { .. }
+    )
+}
+
+// makes the value of an expression have the type it is assigned to
+fn convert(node: &mut AST, from: &VarType, to: &VarType) {
+    if from.base() != to.base() {
+        let expr = std::mem::replace(node, AST::NoOp);
+        *node = AST::Convert {
+            expr: Box::new(expr),
+            var_type: to.base(),
+        };
+    }
+}
+
+// makes the interpreter check the bounds of a value stored to a subrange
+fn range_check(node: &mut AST, to: &VarType, token: &token::Token) {
+    if let VarType::SUBRANGE { .. } = to {
+        let expr = std::mem::replace(node, AST::NoOp);
+        *node = AST::RangeCheck {
+            expr: Box::new(expr),
+            var_type: to.clone(),
+            token: token.clone(),
+        };
+    }
+}
+
+fn binop_type(op: &token::Kind, left: &VarType, right: &VarType) -> Option<VarType> {
+    let is_text = |var_type: &VarType| matches!(var_type, VarType::STRING | VarType::CHAR);
+    let sets = left.is_set() && right.is_set() && (left.accepts(right) || right.accepts(left));
+    match op {
+        // union, difference and intersection keep the type of the set which has elements
+        token::Kind::PLUS | token::Kind::MINUS | token::Kind::MUL if sets => {
+            Some(if right.accepts(left) { right } else { left }.clone())
+        }
+        // inclusion of sets
+        token::Kind::EQUAL
+        | token::Kind::NOT_EQUAL
+        | token::Kind::LESS_EQUAL
+        | token::Kind::GREATER_EQUAL
+            if sets =>
+        {
+            Some(VarType::BOOLEAN)
+        }
+        token::Kind::IN => match right {
+            VarType::SET { element: None } if left.is_ordinal() => Some(VarType::BOOLEAN),
+            VarType::SET {
+                element: Some(element),
+            } if element.base() == *left => Some(VarType::BOOLEAN),
+            _ => None,
+        },
+        token::Kind::PLUS if is_text(left) && is_text(right) => Some(VarType::STRING),
+        token::Kind::PLUS | token::Kind::MINUS | token::Kind::MUL => {
+            if !left.is_numeric() || !right.is_numeric() {
+                None
+            } else if *left == VarType::REAL || *right == VarType::REAL {
+                Some(VarType::REAL)
+            } else {
+                Some(VarType::INTEGER)
+            }
+        }
+        token::Kind::FLOAT_DIV if left.is_numeric() && right.is_numeric() => Some(VarType::REAL),
+        token::Kind::INTEGER_DIV | token::Kind::MOD | token::Kind::SHL | token::Kind::SHR
+            if *left == VarType::INTEGER && *right == VarType::INTEGER =>
+        {
+            Some(VarType::INTEGER)
+        }
+        // logical on booleans and bitwise on integers
+        token::Kind::AND | token::Kind::OR | token::Kind::XOR
+            if left == right && matches!(left, VarType::BOOLEAN | VarType::INTEGER) =>
+        {
+            Some(left.clone())
+        }
+        // pointers can only be compared for equality
+        token::Kind::EQUAL | token::Kind::NOT_EQUAL
+            if left.is_pointer() && (left.accepts(right) || right.accepts(left)) =>
+        {
+            Some(VarType::BOOLEAN)
+        }
+        token::Kind::EQUAL
+        | token::Kind::NOT_EQUAL
+        | token::Kind::LESS
+        | token::Kind::LESS_EQUAL
+        | token::Kind::GREATER
+        | token::Kind::GREATER_EQUAL
+            if (left == right && left.is_ordinal())
+                || (left.is_numeric() && right.is_numeric())
+                || (is_text(left) && is_text(right)) =>
+        {
+            Some(VarType::BOOLEAN)
+        }
+        _ => None,
+    }
+}
 
 pub struct SemanticAnalyzer {
     current_scope: ScopedSymbolTable,
     debug_scope: bool,
+    // control variables of the enclosing FOR loops as (name, scope level)
+    for_variables: Vec<(String, usize)>,
+    // number of the enclosing WITH statements
+    with_depth: usize,
+    // number of the enclosing loops
+    loop_depth: usize,
+    // pointers to types which are not declared yet with the tokens of the pointed types
+    forward_pointers: Vec<(VarType, token::Token)>,
+    // routines declared forward in the current block with the tokens of their names
+    forward_routines: Vec<(String, token::Token)>,
+    // the nested statements and sequences enclosing the current statement
+    statement_path: Vec<usize>,
+    statement_count: usize,
+    // labels defined in the current block with the paths to their statements
+    defined_labels: Vec<(String, Vec<usize>)>,
+    // gotos of the current block with the paths to them
+    gotos: Vec<(String, Vec<usize>, token::Token)>,
 }
 
 impl SemanticAnalyzer {
@@ -13,6 +200,15 @@ impl SemanticAnalyzer {
         SemanticAnalyzer {
             current_scope: ScopedSymbolTable::new("None".to_string(), 0, debug_scope),
             debug_scope,
+            for_variables: Vec::new(),
+            with_depth: 0,
+            loop_depth: 0,
+            forward_pointers: Vec::new(),
+            forward_routines: Vec::new(),
+            statement_path: Vec::new(),
+            statement_count: 0,
+            defined_labels: Vec::new(),
+            gotos: Vec::new(),
         }
     }
 
@@ -21,16 +217,876 @@ impl SemanticAnalyzer {
             println!("{}", line);
         }
     }
-    pub fn visit_node(&mut self, node: &mut AST) -> Result<(), Error> {
+    fn visit_expr(&mut self, node: &mut AST, token: &token::Token) -> Result<VarType, Error> {
+        match self.visit_node(node)? {
+            Some(var_type) => Ok(var_type),
+            None => Err(Error::TYPE_MISMATCH(token.clone())),
+        }
+    }
+
+    // visits statements which a goto from outside of them can not jump into,
+    // each of them gets a new place in the path of the enclosed statements
+    fn nested_statements(&mut self, statements: &mut [AST]) -> Result<(), Error> {
+        self.statement_count += 1;
+        self.statement_path.push(self.statement_count);
+        let res = statements
+            .iter_mut()
+            .try_for_each(|statement| self.visit_node(statement).map(|_| ()));
+        self.statement_path.pop();
+        res
+    }
+
+    // checks that the name is not declared in the current scope yet,
+    // a declaration of the program replaces a built-in routine
+    fn check_new_id(&self, id: &str, token: &token::Token) -> Result<(), Error> {
+        match self.current_scope.lookup_current_only(id) {
+            Some(Symbol::BuiltIn { .. }) | None => Ok(()),
+            Some(_) => Err(Error::DUPLICATE_ID(token.clone())),
+        }
+    }
+
+    // finds a label declared in the current block
+    fn label(&self, label: &str, token: &token::Token) -> Result<String, Error> {
+        match self.current_scope.lookup(label) {
+            Some(Symbol::Label { name, scope_level })
+                if scope_level == self.current_scope.scope_level() =>
+            {
+                Ok(name)
+            }
+            _ => Err(Error::UNDECLARED_LABEL(token.clone())),
+        }
+    }
+
+    fn visit_routine(
+        &mut self,
+        id: &str,
+        params: &mut Vec<Param>,
+        mut return_type: Option<&mut Option<TypeSpec>>,
+        block_node: Option<&mut AST>,
+        token: &token::Token,
+    ) -> Result<(), Error> {
+        let forward = match self.current_scope.lookup_current_only(id) {
+            Some(Symbol::Procedure {
+                params,
+                body,
+                forward: true,
+                ..
+            }) => Some((params, None, body)),
+            Some(Symbol::Function {
+                params,
+                return_type,
+                body,
+                forward: true,
+                ..
+            }) => Some((params, Some(return_type), body)),
+            // a routine of the program replaces the built-in one
+            Some(Symbol::BuiltIn { .. }) | None => None,
+            Some(_) => return Err(Error::DUPLICATE_ID(token.clone())),
+        };
+        // the full declaration of a forward routine may leave out its header
+        if let (Some((forward_params, forward_type, _)), Some(_)) = (&forward, &block_node) {
+            if params.is_empty() {
+                params.clone_from(forward_params);
+            }
+            if let (Some(return_type @ None), Some(forward_type)) =
+                (return_type.as_deref_mut(), forward_type)
+            {
+                *return_type = Some(TypeSpec::Resolved(forward_type.clone()));
+            }
+        }
+        for param in params.iter_mut() {
+            self.resolve_type(&mut param.ttype, None)?;
+        }
+        let return_type = match return_type {
+            Some(Some(return_type)) => Some(self.resolve_type(return_type, None)?),
+            Some(None) => return Err(Error::TYPE_EXPECTED(token.clone())),
+            None => None,
+        };
+        let params: &[Param] = params;
+        let symbol = |body: &RoutineBody, forward: bool| match &return_type {
+            Some(return_type) => Symbol::Function {
+                name: id.to_string(),
+                params: params.to_vec(),
+                return_type: return_type.clone(),
+                body: body.clone(),
+                forward,
+                scope_level: 0,
+            },
+            None => Symbol::Procedure {
+                name: id.to_string(),
+                params: params.to_vec(),
+                body: body.clone(),
+                forward,
+                scope_level: 0,
+            },
+        };
+        let Some(block_node) = block_node else {
+            if forward.is_some() {
+                return Err(Error::DUPLICATE_ID(token.clone()));
+            }
+            // the block must be given later in the same declarations
+            self.current_scope
+                .insert(symbol(&RoutineBody::default(), true));
+            self.forward_routines.push((id.to_string(), token.clone()));
+            return Ok(());
+        };
+        // calls made through the forward declaration share its body
+        let body = match forward {
+            Some((forward_params, forward_type, body)) => {
+                // a header given again must be the same as the forward one
+                if forward_type != return_type || !same_parameters(&forward_params, params) {
+                    return Err(Error::HEADER_MISMATCH(token.clone()));
+                }
+                body
+            }
+            None => RoutineBody::default(),
+        };
+        self.current_scope.insert(symbol(&body, false));
+        self.log(format!("ENTER scope: {}", id));
+        let current_scope_level = self.current_scope.scope_level();
+        let prev_scope = std::mem::replace(
+            &mut self.current_scope,
+            ScopedSymbolTable::new(id.to_string(), current_scope_level + 1, self.debug_scope),
+        );
+        self.current_scope.set_enclosing_scope(prev_scope);
+
+        params.iter().for_each(|param| {
+            let var_symbol = Symbol::Var {
+                name: param.id.clone(),
+                kind: param.ttype.var_type().clone(),
+                read_only: param.mode == ParamMode::CONST,
+                scope_level: 0,
+            };
+            self.current_scope.insert(var_symbol);
+        });
+        if let Some(return_type) = &return_type {
+            // a parameter cannot take the name of the result
+            self.check_new_id(RETURN_VALUE, token)?;
+            self.current_scope.insert(Symbol::Var {
+                name: RETURN_VALUE.to_string(),
+                kind: return_type.clone(),
+                read_only: false,
+                scope_level: 0,
+            });
+        }
+
+        self.visit_node(block_node)?;
+
+        self.log(format!("{}", self.current_scope));
+        self.current_scope = self.current_scope.enclosing_scope();
+        body.set(block_node.clone());
+        self.log(format!("LEAVE scope: {}", id));
+        Ok(())
+    }
+
+    fn check_arguments(
+        &mut self,
+        ast_params: &mut [AST],
+        params: &[Param],
+        token: &token::Token,
+    ) -> Result<(), Error> {
+        if ast_params.len() != params.len() {
+            return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+        }
+        for (actual, formal) in ast_params.iter_mut().zip(params) {
+            if let ParamMode::VAR | ParamMode::OUT = formal.mode {
+                self.check_variable(actual, token)?;
+            }
+            let actual_type = self.visit_expr(actual, token)?;
+            let compatible = match formal.mode {
+                // a variable passed by reference must have exactly the same type
+                ParamMode::VAR | ParamMode::OUT => *formal.ttype.var_type() == actual_type,
+                ParamMode::VALUE | ParamMode::CONST => {
+                    formal.ttype.var_type().accepts(&actual_type)
+                }
+            };
+            if !compatible {
+                return Err(Error::TYPE_MISMATCH(token.clone()));
+            }
+            convert(actual, &actual_type, formal.ttype.var_type());
+            if let ParamMode::VALUE | ParamMode::CONST = formal.mode {
+                range_check(actual, formal.ttype.var_type(), token);
+            }
+        }
+        Ok(())
+    }
+
+    // checks that the left side of an assignment can be modified
+    fn assignment_target(&mut self, node: &mut AST, token: &token::Token) -> Result<(), Error> {
+        let (id, var_token, var_level) = match node {
+            AST::Var {
+                id,
+                token,
+                scope_level,
+            } => (id, token, scope_level),
+            AST::Index { array, .. } => return self.assignment_target(array, token),
+            AST::Field { record, .. } => return self.assignment_target(record, token),
+            // a dynamic variable can be modified through any pointer
+            AST::Deref { .. } => return Ok(()),
+            _ => return Err(Error::INVALID_ASSIGNMENT(token.clone())),
+        };
+        match self.current_scope.lookup(id) {
+            Some(Symbol::Var {
+                read_only: true, ..
+            }) => Err(Error::INVALID_ASSIGNMENT(token.clone())),
+            Some(Symbol::Var {
+                name, scope_level, ..
+            }) => {
+                if self.for_variables.contains(&(name, scope_level)) {
+                    return Err(Error::FOR_VARIABLE_ASSIGNMENT(token.clone()));
+                }
+                Ok(())
+            }
+            Some(Symbol::Function {
+                name, scope_level, ..
+            }) if self.current_scope.is_within(&name, scope_level + 1) => {
+                // assigning to the function name sets its result,
+                // which is declared in the scope of the function
+                *id = RETURN_VALUE.to_string();
+                *var_level = scope_level + 1;
+                Ok(())
+            }
+            Some(Symbol::Field { record, .. }) => {
+                let mut record = AST::Var {
+                    id: record,
+                    token: var_token.clone(),
+                    scope_level: 0,
+                };
+                self.assignment_target(&mut record, token)
+            }
+            Some(_) => Err(Error::INVALID_ASSIGNMENT(token.clone())),
+            None => Err(Error::ID_NOT_FOUND(var_token.clone())),
+        }
+    }
+
+    // checks that an argument passed by reference is a variable which can be modified
+    fn check_variable(&mut self, node: &AST, token: &token::Token) -> Result<(), Error> {
+        let id = match node {
+            AST::Var { id, .. } => id,
+            AST::Index { array, .. } => return self.check_variable(array, token),
+            AST::Field { record, .. } => return self.check_variable(record, token),
+            AST::Deref { .. } => return Ok(()),
+            _ => return Err(Error::VARIABLE_EXPECTED(token.clone())),
+        };
+        match self.current_scope.lookup(id) {
+            Some(Symbol::Var {
+                name,
+                read_only: false,
+                scope_level,
+                ..
+            }) => {
+                if self.for_variables.contains(&(name, scope_level)) {
+                    return Err(Error::FOR_VARIABLE_ASSIGNMENT(token.clone()));
+                }
+                Ok(())
+            }
+            Some(Symbol::Field { record, .. }) => {
+                let record = AST::Var {
+                    id: record,
+                    token: token.clone(),
+                    scope_level: 0,
+                };
+                self.check_variable(&record, token)
+            }
+            Some(_) => Err(Error::VARIABLE_EXPECTED(token.clone())),
+            None => Ok(()),
+        }
+    }
+
+    // checks the arguments of a routine provided by the interpreter
+    // and returns the type of its result
+    fn visit_builtin(
+        &mut self,
+        builtin: BuiltIn,
+        args: &mut [AST],
+        arg_types: &mut Vec<VarType>,
+        token: &token::Token,
+    ) -> Result<Option<VarType>, Error> {
+        match builtin {
+            BuiltIn::WRITE | BuiltIn::WRITELN => {
+                for (n, arg) in args.iter_mut().enumerate() {
+                    let var_type = self.write_argument(arg, token)?;
+                    if n == 0 && var_type == VarType::TEXT && !matches!(arg, AST::Format { .. }) {
+                        // the text is written into the file given first
+                        self.check_variable(arg, token)?;
+                        arg_types.push(var_type);
+                    } else if !var_type.is_ordinal()
+                        && !var_type.is_numeric()
+                        && var_type.base() != VarType::STRING
+                    {
+                        return Err(Error::TYPE_MISMATCH(token.clone()));
+                    }
+                }
+                Ok(None)
+            }
+            BuiltIn::READ | BuiltIn::READLN => {
+                // the values are stored into the arguments, the interpreter
+                // parses the input by their types, a file can be given first
+                for (n, arg) in args.iter_mut().enumerate() {
+                    self.check_variable(arg, token)?;
+                    let var_type = self.visit_expr(arg, token)?;
+                    if !matches!(
+                        var_type.base(),
+                        VarType::INTEGER | VarType::REAL | VarType::CHAR | VarType::STRING
+                    ) && (n > 0 || var_type != VarType::TEXT)
+                    {
+                        return Err(Error::TYPE_MISMATCH(token.clone()));
+                    }
+                    arg_types.push(var_type);
+                }
+                Ok(None)
+            }
+            BuiltIn::ASSIGN => {
+                let [file, name] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.file_argument(file, token)?;
+                self.value_arguments(std::slice::from_mut(name), &[VarType::STRING], token)?;
+                Ok(None)
+            }
+            BuiltIn::RESET | BuiltIn::REWRITE | BuiltIn::APPEND | BuiltIn::CLOSE => {
+                let [file] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.file_argument(file, token)?;
+                Ok(None)
+            }
+            BuiltIn::EOF | BuiltIn::EOLN => {
+                // without a file the standard input is checked
+                match args {
+                    [] => {}
+                    [file] => self.file_argument(file, token)?,
+                    _ => return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone())),
+                }
+                Ok(Some(VarType::BOOLEAN))
+            }
+            BuiltIn::IORESULT => {
+                if !args.is_empty() {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                }
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::EXIT => {
+                if !args.is_empty() {
+                    // only a function gives a value to exit with
+                    let scope_level = self.current_scope.scope_level();
+                    let return_type = match self.current_scope.lookup(RETURN_VALUE) {
+                        Some(Symbol::Var {
+                            kind,
+                            scope_level: level,
+                            ..
+                        }) if level == scope_level => kind,
+                        _ => return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone())),
+                    };
+                    self.value_arguments(args, &[return_type], token)?;
+                }
+                Ok(None)
+            }
+            BuiltIn::BREAK | BuiltIn::CONTINUE => {
+                if !args.is_empty() {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                }
+                if self.loop_depth == 0 {
+                    return Err(Error::NOT_IN_LOOP(token.clone()));
+                }
+                Ok(None)
+            }
+            BuiltIn::HALT => {
+                if args.len() > 1 {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                }
+                self.value_arguments(args, &vec![VarType::INTEGER; args.len()], token)?;
+                Ok(None)
+            }
+            BuiltIn::ABS | BuiltIn::SQR => {
+                let var_type = self.numeric_argument(args, token)?;
+                Ok(Some(var_type.base()))
+            }
+            BuiltIn::SQRT
+            | BuiltIn::SIN
+            | BuiltIn::COS
+            | BuiltIn::ARCTAN
+            | BuiltIn::EXP
+            | BuiltIn::LN => {
+                self.numeric_argument(args, token)?;
+                Ok(Some(VarType::REAL))
+            }
+            BuiltIn::ROUND | BuiltIn::TRUNC => {
+                self.numeric_argument(args, token)?;
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::ODD => {
+                if self.single_argument(args, token)?.base() != VarType::INTEGER {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                Ok(Some(VarType::BOOLEAN))
+            }
+            BuiltIn::ORD => {
+                self.ordinal_argument(args, token)?;
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::CHR => {
+                if self.single_argument(args, token)?.base() != VarType::INTEGER {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                Ok(Some(VarType::CHAR))
+            }
+            BuiltIn::SUCC | BuiltIn::PRED => {
+                let var_type = self.ordinal_argument(args, token)?;
+                Ok(Some(var_type.base()))
+            }
+            BuiltIn::LOW | BuiltIn::HIGH => {
+                // the bound depends only on the type, so it replaces the argument
+                let value = self.type_bound(builtin == BuiltIn::HIGH, args, token)?;
+                args[0] = literal(&value, &value.var_type());
+                Ok(Some(value.var_type()))
+            }
+            BuiltIn::INC | BuiltIn::DEC => {
+                let (variable, amount) = match args {
+                    [variable] => (variable, None),
+                    [variable, amount] => (variable, Some(amount)),
+                    _ => return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone())),
+                };
+                self.check_variable(variable, token)?;
+                let var_type = self.visit_expr(variable, token)?;
+                if !var_type.is_ordinal() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                // the interpreter checks the result against the bounds of the variable
+                arg_types.push(var_type);
+                if let Some(amount) = amount {
+                    if self.visit_expr(amount, token)?.base() != VarType::INTEGER {
+                        return Err(Error::TYPE_MISMATCH(token.clone()));
+                    }
+                }
+                Ok(None)
+            }
+            BuiltIn::NEW => {
+                // the interpreter creates a variable of the pointed type
+                let [pointer] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.check_variable(pointer, token)?;
+                let pointer = self.visit_expr(pointer, token)?;
+                arg_types.push(self.pointer_target(&pointer, token)?);
+                Ok(None)
+            }
+            BuiltIn::DISPOSE => match self.single_argument(args, token)? {
+                VarType::POINTER { .. } => Ok(None),
+                _ => Err(Error::TYPE_MISMATCH(token.clone())),
+            },
+            BuiltIn::LENGTH => {
+                self.value_arguments(args, &[VarType::STRING], token)?;
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::COPY => {
+                self.value_arguments(
+                    args,
+                    &[VarType::STRING, VarType::INTEGER, VarType::INTEGER],
+                    token,
+                )?;
+                Ok(Some(VarType::STRING))
+            }
+            BuiltIn::POS => {
+                self.value_arguments(args, &[VarType::STRING, VarType::STRING], token)?;
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::CONCAT => {
+                if args.is_empty() {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                }
+                self.value_arguments(args, &vec![VarType::STRING; args.len()], token)?;
+                Ok(Some(VarType::STRING))
+            }
+            BuiltIn::INSERT => {
+                let [source, text, index] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.value_arguments(std::slice::from_mut(source), &[VarType::STRING], token)?;
+                self.string_variable(text, token)?;
+                self.value_arguments(std::slice::from_mut(index), &[VarType::INTEGER], token)?;
+                Ok(None)
+            }
+            BuiltIn::DELETE => {
+                let [text, numbers @ ..] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.string_variable(text, token)?;
+                self.value_arguments(numbers, &[VarType::INTEGER, VarType::INTEGER], token)?;
+                Ok(None)
+            }
+            BuiltIn::UPCASE => {
+                let var_type = self.single_argument(args, token)?.base();
+                if !matches!(var_type, VarType::CHAR | VarType::STRING) {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                Ok(Some(var_type))
+            }
+            BuiltIn::INTTOSTR => {
+                self.value_arguments(args, &[VarType::INTEGER], token)?;
+                Ok(Some(VarType::STRING))
+            }
+            BuiltIn::STRTOINT => {
+                self.value_arguments(args, &[VarType::STRING], token)?;
+                Ok(Some(VarType::INTEGER))
+            }
+            BuiltIn::VAL => {
+                // the interpreter parses the text by the type of the variable
+                let [text, value, code] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                self.value_arguments(std::slice::from_mut(text), &[VarType::STRING], token)?;
+                self.check_variable(value, token)?;
+                let var_type = self.visit_expr(value, token)?;
+                if !var_type.is_numeric() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                arg_types.push(var_type);
+                self.check_variable(code, token)?;
+                if self.visit_expr(code, token)?.base() != VarType::INTEGER {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                Ok(None)
+            }
+            BuiltIn::STR => {
+                // the number is formatted the same way write does it
+                let [value, text] = args else {
+                    return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+                };
+                if !self.write_argument(value, token)?.is_numeric() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                self.string_variable(text, token)?;
+                Ok(None)
+            }
+        }
+    }
+
+    // checks that the arguments can be passed as values of the given types
+    fn value_arguments(
+        &mut self,
+        args: &mut [AST],
+        types: &[VarType],
+        token: &token::Token,
+    ) -> Result<(), Error> {
+        if args.len() != types.len() {
+            return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()));
+        }
+        for (arg, var_type) in args.iter_mut().zip(types) {
+            let arg_type = self.visit_expr(arg, token)?;
+            if !var_type.accepts(&arg_type) {
+                return Err(Error::TYPE_MISMATCH(token.clone()));
+            }
+            convert(arg, &arg_type, var_type);
+        }
+        Ok(())
+    }
+
+    // checks a string variable which is modified by a built-in procedure
+    fn string_variable(&mut self, node: &mut AST, token: &token::Token) -> Result<(), Error> {
+        self.check_variable(node, token)?;
+        if self.visit_expr(node, token)?.base() != VarType::STRING {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        }
+        Ok(())
+    }
+
+    // the type a pointer points to, it is looked up when the pointer is used
+    // because it may be declared after the pointer type
+    fn pointer_target(&self, pointer: &VarType, token: &token::Token) -> Result<VarType, Error> {
+        let VarType::POINTER {
+            target,
+            scope_level,
+        } = pointer
+        else {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        };
+        self.current_scope
+            .lookup_type(target, *scope_level)
+            .ok_or_else(|| Error::ID_NOT_FOUND(token.clone()))
+    }
+
+    // the first or the last value of the type named by the argument
+    // or of the type of the argument
+    fn type_bound(
+        &mut self,
+        high: bool,
+        args: &mut [AST],
+        token: &token::Token,
+    ) -> Result<CalcResult, Error> {
+        let type_name = match args {
+            [AST::Var { id, .. }] => Some(id.clone()),
+            _ => None,
+        };
+        let var_type = match type_name.and_then(|id| self.current_scope.lookup(&id)) {
+            Some(Symbol::Type { kind, .. }) => kind,
+            _ => self.single_argument(args, token)?,
+        };
+        bound(&var_type, high).ok_or_else(|| Error::TYPE_MISMATCH(token.clone()))
+    }
+
+    fn single_argument(
+        &mut self,
+        args: &mut [AST],
+        token: &token::Token,
+    ) -> Result<VarType, Error> {
+        match args {
+            [arg] => self.visit_expr(arg, token),
+            _ => Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone())),
+        }
+    }
+
+    fn numeric_argument(
+        &mut self,
+        args: &mut [AST],
+        token: &token::Token,
+    ) -> Result<VarType, Error> {
+        let var_type = self.single_argument(args, token)?;
+        if !var_type.is_numeric() {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        }
+        Ok(var_type)
+    }
+
+    fn ordinal_argument(
+        &mut self,
+        args: &mut [AST],
+        token: &token::Token,
+    ) -> Result<VarType, Error> {
+        let var_type = self.single_argument(args, token)?;
+        if !var_type.is_ordinal() {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        }
+        Ok(var_type)
+    }
+
+    // checks a text file variable passed to a built-in routine
+    fn file_argument(&mut self, node: &mut AST, token: &token::Token) -> Result<(), Error> {
+        self.check_variable(node, token)?;
+        if self.visit_expr(node, token)? != VarType::TEXT {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        }
+        Ok(())
+    }
+
+    // checks the width and the precision of an argument of write and returns its type
+    fn write_argument(&mut self, arg: &mut AST, token: &token::Token) -> Result<VarType, Error> {
+        let var_type = match arg {
+            AST::Format {
+                expr,
+                width,
+                precision,
+                token,
+            } => {
+                let var_type = self.visit_expr(expr, token)?;
+                if self.visit_expr(width, token)?.base() != VarType::INTEGER {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                if let Some(precision) = precision {
+                    if var_type.base() != VarType::REAL
+                        || self.visit_expr(precision, token)?.base() != VarType::INTEGER
+                    {
+                        return Err(Error::TYPE_MISMATCH(token.clone()));
+                    }
+                }
+                var_type
+            }
+            arg => self.visit_expr(arg, token)?,
+        };
+        Ok(var_type)
+    }
+
+    // evaluates a constant expression and replaces it with its value
+    fn constant(&mut self, node: &mut AST, token: &token::Token) -> Result<CalcResult, Error> {
+        let var_type = self.visit_expr(node, token)?;
+        match constant_value(node) {
+            Some(value) => {
+                *node = literal(&value, &var_type);
+                Ok(value)
+            }
+            None => Err(Error::CONSTANT_EXPECTED(token.clone())),
+        }
+    }
+
+    // checks a case label and returns its ordinal value
+    fn case_label(
+        &mut self,
+        node: &mut AST,
+        selector_type: &VarType,
+        token: &token::Token,
+    ) -> Result<i32, Error> {
+        let value = self.constant(node, token)?;
+        if value.var_type().base() != selector_type.base() {
+            return Err(Error::TYPE_MISMATCH(token.clone()));
+        }
+        Ok(value.as_ordinal())
+    }
+
+    // finds the type described by the type specification, `name` is given
+    // when the type is declared in the TYPE section
+    fn resolve_type(&mut self, spec: &mut TypeSpec, name: Option<&str>) -> Result<VarType, Error> {
+        let var_type = match spec {
+            TypeSpec::Resolved(var_type) => return Ok(var_type.clone()),
+            TypeSpec::Named { id, token } => match self.current_scope.lookup(id) {
+                Some(Symbol::Type { kind, .. }) => kind,
+                Some(_) => return Err(Error::TYPE_EXPECTED(token.clone())),
+                None => return Err(Error::ID_NOT_FOUND(token.clone())),
+            },
+            TypeSpec::Subrange { low, high, token } => {
+                let low = self.constant(low, token)?;
+                let high = self.constant(high, token)?;
+                let base = low.var_type().base();
+                if !base.is_ordinal() || base != high.var_type().base() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                if high.as_ordinal() < low.as_ordinal() {
+                    return Err(Error::INVALID_RANGE(token.clone()));
+                }
+                VarType::SUBRANGE {
+                    base: Box::new(base),
+                    low: low.as_ordinal(),
+                    high: high.as_ordinal(),
+                }
+            }
+            TypeSpec::Array {
+                index,
+                element,
+                token,
+            } => {
+                let mut indexes = Vec::new();
+                for index in index {
+                    let index = self.resolve_type(index, None)?;
+                    if index.bounds().is_none() {
+                        return Err(Error::TYPE_MISMATCH(token.clone()));
+                    }
+                    indexes.push(index);
+                }
+                let element = self.resolve_type(element, None)?;
+                indexes
+                    .into_iter()
+                    .rev()
+                    .fold(element, |element, index| VarType::ARRAY {
+                        index: Box::new(index),
+                        element: Box::new(element),
+                    })
+            }
+            TypeSpec::Record { fields, .. } => {
+                let mut resolved: Vec<(String, VarType)> = Vec::new();
+                for field in fields {
+                    if let AST::VarDecl {
+                        id,
+                        var_type,
+                        token,
+                    } = field
+                    {
+                        let kind = self.resolve_type(var_type, None)?;
+                        if resolved
+                            .iter()
+                            .any(|(name, _)| name.eq_ignore_ascii_case(id))
+                        {
+                            return Err(Error::DUPLICATE_ID(token.clone()));
+                        }
+                        resolved.push((id.clone(), kind));
+                    }
+                }
+                VarType::RECORD {
+                    name: name.unwrap_or("record").to_string(),
+                    fields: resolved,
+                }
+            }
+            TypeSpec::Set { element, token } => {
+                let element = self.resolve_type(element, None)?;
+                match element.bounds() {
+                    Some((low, high)) if low >= 0 && high < SET_SIZE => {}
+                    _ => return Err(Error::TYPE_MISMATCH(token.clone())),
+                }
+                VarType::SET {
+                    element: Some(Box::new(element)),
+                }
+            }
+            TypeSpec::Pointer { id, token } => match self.current_scope.lookup(id) {
+                Some(Symbol::Type {
+                    name, scope_level, ..
+                }) => VarType::POINTER {
+                    target: name,
+                    scope_level,
+                },
+                Some(_) => return Err(Error::TYPE_EXPECTED(token.clone())),
+                None => {
+                    let pointer = VarType::POINTER {
+                        target: id.clone(),
+                        scope_level: self.current_scope.scope_level(),
+                    };
+                    self.forward_pointers.push((pointer.clone(), token.clone()));
+                    pointer
+                }
+            },
+            TypeSpec::Enum { values, token } => {
+                let var_type = VarType::ENUM {
+                    name: name.map_or(format!("({})", values.join(", ")), str::to_string),
+                    values: values.clone(),
+                };
+                for (ordinal, value) in values.iter().enumerate() {
+                    match self.current_scope.lookup_current_only(value) {
+                        // several variables declared with the same anonymous type
+                        Some(Symbol::Const { kind, .. }) if kind == var_type => {}
+                        // a declaration of the program replaces a built-in routine
+                        Some(Symbol::BuiltIn { .. }) | None => {
+                            self.current_scope.insert(Symbol::Const {
+                                name: value.clone(),
+                                value: CalcResult::ENUM(ordinal as i32, var_type.clone()),
+                                kind: var_type.clone(),
+                                scope_level: 0,
+                            })
+                        }
+                        Some(_) => return Err(Error::DUPLICATE_ID(token.clone())),
+                    }
+                }
+                var_type
+            }
+        };
+        *spec = TypeSpec::Resolved(var_type.clone());
+        Ok(var_type)
+    }
+
+    pub fn visit_node(&mut self, node: &mut AST) -> Result<Option<VarType>, Error> {
         match node {
             AST::Block {
                 declaration_nodes,
                 compound_nodes,
             } => {
+                let outer_pointers = std::mem::take(&mut self.forward_pointers);
+                let outer_routines = std::mem::take(&mut self.forward_routines);
                 for declaration in declaration_nodes {
                     self.visit_node(declaration)?;
                 }
+                // a pointed type must be declared in the same block as the pointer
+                let pointers = std::mem::replace(&mut self.forward_pointers, outer_pointers);
+                for (pointer, token) in pointers {
+                    self.pointer_target(&pointer, &token)?;
+                }
+                // and so must be the block of a routine declared forward
+                let routines = std::mem::replace(&mut self.forward_routines, outer_routines);
+                for (id, token) in routines {
+                    if let Some(
+                        Symbol::Procedure { forward: true, .. }
+                        | Symbol::Function { forward: true, .. },
+                    ) = self.current_scope.lookup_current_only(&id)
+                    {
+                        return Err(Error::UNSOLVED_FORWARD(token));
+                    }
+                }
                 self.visit_node(compound_nodes)?;
+                // nested routines are done before, so all the labels and gotos are of this block,
+                // a goto can jump only to a statement of its own or an enclosing sequence
+                let labels = std::mem::take(&mut self.defined_labels);
+                for (label, path, token) in std::mem::take(&mut self.gotos) {
+                    match labels.iter().find(|(name, _)| *name == label) {
+                        Some((_, label_path)) if path.starts_with(label_path) => {}
+                        Some(_) => return Err(Error::INVALID_GOTO(token)),
+                        None => return Err(Error::UNDEFINED_LABEL(token)),
+                    }
+                }
             }
             AST::Program { name: _, block } => {
                 self.log("ENTER scope: global".to_string());
@@ -46,119 +1102,530 @@ impl SemanticAnalyzer {
                 self.current_scope = self.current_scope.enclosing_scope();
                 self.log("LEAVE scope: global".to_string());
             }
-            AST::Compound { children } => {
-                for child in children {
-                    self.visit_node(child)?;
-                }
-            }
+            AST::Compound { children } => self.nested_statements(children)?,
             AST::ProcedureDecl {
                 id,
                 params,
                 block_node,
+                token,
+            } => self.visit_routine(id, params, None, block_node.as_deref_mut(), token)?,
+            AST::FunctionDecl {
+                id,
+                params,
+                return_type,
+                block_node,
+                token,
+            } => self.visit_routine(
+                id,
+                params,
+                Some(return_type),
+                block_node.as_deref_mut(),
+                token,
+            )?,
+            AST::BuiltInCall {
+                builtin,
+                args,
+                arg_types,
+                token,
+            } => return self.visit_builtin(*builtin, args, arg_types, token),
+            // width and precision are allowed only in arguments of write
+            AST::Format { token, .. } => return Err(Error::UNEXPECTED_TOKEN(token.clone())),
+            AST::NumInteger { .. } => return Ok(Some(VarType::INTEGER)),
+            AST::NumReal { .. } => return Ok(Some(VarType::REAL)),
+            AST::Boolean { .. } => return Ok(Some(VarType::BOOLEAN)),
+            AST::Char { .. } => return Ok(Some(VarType::CHAR)),
+            AST::Str { .. } => return Ok(Some(VarType::STRING)),
+            AST::Convert { var_type, .. } | AST::RangeCheck { var_type, .. } => {
+                return Ok(Some(var_type.clone()))
+            }
+            AST::Constant { var_type, .. } => return Ok(Some(var_type.clone())),
+            AST::NoOp => {}
+            AST::LabelDecl { label, token } => {
+                self.check_new_id(label, token)?;
+                self.current_scope.insert(Symbol::Label {
+                    name: label.clone(),
+                    scope_level: 0,
+                });
+            }
+            AST::Labeled {
+                label,
+                statement,
+                token,
+            } => {
+                *label = self.label(label, token)?;
+                if self.defined_labels.iter().any(|(name, _)| name == label) {
+                    return Err(Error::DUPLICATE_LABEL(token.clone()));
+                }
+                self.defined_labels
+                    .push((label.clone(), self.statement_path.clone()));
+                self.visit_node(statement)?;
+            }
+            AST::Goto { label, token } => {
+                *label = self.label(label, token)?;
+                self.gotos
+                    .push((label.clone(), self.statement_path.clone(), token.clone()));
+            }
+            AST::UnaryOp { op, expr, token } => {
+                let expr_type = self.visit_expr(expr, token)?;
+                return match op {
+                    token::Kind::NOT
+                        if matches!(expr_type.base(), VarType::BOOLEAN | VarType::INTEGER) =>
+                    {
+                        Ok(Some(expr_type.base()))
+                    }
+                    token::Kind::PLUS | token::Kind::MINUS if expr_type.is_numeric() => {
+                        Ok(Some(expr_type))
+                    }
+                    _ => Err(Error::TYPE_MISMATCH(token.clone())),
+                };
+            }
+            AST::Assign { left, right, token } => {
+                self.assignment_target(left, token)?;
+                let right_type = self.visit_expr(right, token)?;
+                let left_type = self.visit_expr(left, token)?;
+                if !left_type.accepts(&right_type) {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                convert(right, &right_type, &left_type);
+                range_check(right, &left_type, token);
+            }
+            AST::BinOp {
+                left,
+                right,
+                op,
+                token,
+            } => {
+                let right_type = self.visit_expr(right, token)?;
+                let left_type = self.visit_expr(left, token)?;
+                return match binop_type(op, &left_type.base(), &right_type.base()) {
+                    Some(var_type) => Ok(Some(var_type)),
+                    None => Err(Error::TYPE_MISMATCH(token.clone())),
+                };
+            }
+            AST::If {
+                condition,
+                then_node,
+                else_node,
+                token,
             } => {
-                self.current_scope.insert(Symbol::Procedure {
+                if self.visit_expr(condition, token)?.base() != VarType::BOOLEAN {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                self.nested_statements(std::slice::from_mut(then_node))?;
+                if let Some(else_node) = else_node {
+                    self.nested_statements(std::slice::from_mut(else_node))?;
+                }
+            }
+            AST::ConstDecl { id, value, token } => {
+                let constant = self.constant(value, token)?;
+                // the folded literal gives the type of the constant
+                let kind = self.visit_expr(value, token)?;
+                self.check_new_id(id, token)?;
+                self.current_scope.insert(Symbol::Const {
                     name: id.clone(),
-                    params: params.clone(),
-                    block: *block_node.clone(),
+                    value: constant,
+                    kind,
                     scope_level: 0,
                 });
-                self.log(format!("ENTER scope: {}", id));
-                let current_scope_level = self.current_scope.scope_level();
-                let prev_scope = std::mem::replace(
-                    &mut self.current_scope,
-                    ScopedSymbolTable::new(id.clone(), current_scope_level + 1, self.debug_scope),
-                );
-                self.current_scope.set_enclosing_scope(prev_scope);
-
-                params.iter().for_each(|param| {
-                    // use to see same output as origial python implementation
-                    self.current_scope.lookup(&param.ttype.to_string());
-                    let var_symbol = Symbol::Var {
-                        name: param.id.clone(),
-                        kind: param.ttype.clone(),
-                        scope_level: 0,
-                    };
-                    self.current_scope.insert(var_symbol);
-                });
-
-                self.visit_node(block_node)?;
-
-                self.log(format!("{}", self.current_scope));
-                self.current_scope = self.current_scope.enclosing_scope();
-                // replace with new block
-                self.current_scope.insert(Symbol::Procedure {
+            }
+            AST::TypeDecl {
+                id,
+                type_spec,
+                token,
+            } => {
+                self.check_new_id(id, token)?;
+                let kind = self.resolve_type(type_spec, Some(id))?;
+                self.current_scope.insert(Symbol::Type {
                     name: id.clone(),
-                    params: params.clone(),
-                    block: *block_node.clone(),
+                    kind,
                     scope_level: 0,
                 });
-                self.log(format!("LEAVE scope: {}", id));
-            }
-            AST::NumInteger { value: _ }
-            | AST::NumReal { value: _ }
-            | AST::NoOp
-            | AST::UnaryOp { op: _, expr: _ } => {}
-            AST::Assign {
-                left_id: _,
-                left,
-                right,
-            } => {
-                self.visit_node(right)?;
-                self.visit_node(left)?;
-            }
-            AST::BinOp { left, right, op: _ } => {
-                self.visit_node(right)?;
-                self.visit_node(left)?;
             }
             AST::VarDecl {
                 id,
                 var_type,
                 token,
             } => {
-                // use to see same output as origial python implementation
-                self.current_scope.lookup(&var_type.name());
-                if self.current_scope.lookup_current_only(id).is_some() {
-                    return Err(Error::DUPLICATE_ID(token.clone()));
-                }
+                let kind = self.resolve_type(var_type, None)?;
+                self.check_new_id(id, token)?;
                 self.current_scope.insert(Symbol::Var {
                     name: id.clone(),
-                    kind: var_type.clone(),
+                    kind,
+                    read_only: false,
                     scope_level: 0,
                 });
             }
-            AST::Var { id, token } => {
-                let sym = self.current_scope.lookup(id);
-                if sym.is_none() {
-                    return Err(Error::ID_NOT_FOUND(token.clone()));
+            AST::While {
+                condition,
+                body,
+                token,
+            } => {
+                if self.visit_expr(condition, token)?.base() != VarType::BOOLEAN {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                self.loop_depth += 1;
+                let res = self.nested_statements(std::slice::from_mut(body));
+                self.loop_depth -= 1;
+                res?;
+            }
+            AST::Repeat {
+                children,
+                condition,
+                token,
+            } => {
+                self.loop_depth += 1;
+                let res = self.nested_statements(children);
+                self.loop_depth -= 1;
+                res?;
+                if self.visit_expr(condition, token)?.base() != VarType::BOOLEAN {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+            }
+            AST::Case {
+                selector,
+                branches,
+                else_nodes,
+                token,
+            } => {
+                let selector_type = self.visit_expr(selector, token)?;
+                if !selector_type.is_ordinal() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                let mut ranges: Vec<(i32, i32)> = Vec::new();
+                for branch in branches {
+                    for label in &mut branch.labels {
+                        let low = self.case_label(&mut label.low, &selector_type, &label.token)?;
+                        let high = match &mut label.high {
+                            Some(high) => self.case_label(high, &selector_type, &label.token)?,
+                            None => low,
+                        };
+                        if high < low {
+                            return Err(Error::INVALID_RANGE(label.token.clone()));
+                        }
+                        if ranges.iter().any(|&(a, b)| low <= b && a <= high) {
+                            return Err(Error::DUPLICATE_CASE_LABEL(label.token.clone()));
+                        }
+                        ranges.push((low, high));
+                    }
+                    self.nested_statements(std::slice::from_mut(&mut branch.body))?;
+                }
+                if let Some(else_nodes) = else_nodes {
+                    self.nested_statements(else_nodes)?;
+                }
+            }
+            AST::For {
+                var_id,
+                var,
+                start,
+                end,
+                body,
+                token,
+                ..
+            } => {
+                let control = match self.current_scope.lookup(var_id) {
+                    Some(Symbol::Var {
+                        name, scope_level, ..
+                    }) => (name, scope_level),
+                    _ => {
+                        self.visit_node(var)?;
+                        return Err(Error::INVALID_ASSIGNMENT(token.clone()));
+                    }
+                };
+                if self.for_variables.contains(&control) {
+                    return Err(Error::FOR_VARIABLE_ASSIGNMENT(token.clone()));
+                }
+                let var_type = self.visit_expr(var, token)?;
+                let start_type = self.visit_expr(start, token)?;
+                let end_type = self.visit_expr(end, token)?;
+                if !var_type.is_ordinal()
+                    || start_type.base() != var_type.base()
+                    || end_type.base() != var_type.base()
+                {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                // both bounds are stored in the control variable
+                range_check(start, &var_type, token);
+                range_check(end, &var_type, token);
+                *var_id = control.0.clone();
+
+                self.for_variables.push(control);
+                self.loop_depth += 1;
+                let res = self.nested_statements(std::slice::from_mut(body));
+                self.loop_depth -= 1;
+                self.for_variables.pop();
+                res?;
+            }
+            AST::Var {
+                id,
+                token,
+                scope_level,
+            } => {
+                // the result of an enclosing function is already bound to its scope
+                let symbol = match *scope_level {
+                    0 => self.current_scope.lookup(id),
+                    level => self.current_scope.lookup_at(id, level),
+                };
+                let (value, kind) = match symbol {
+                    Some(Symbol::Var {
+                        name,
+                        kind,
+                        scope_level: level,
+                        ..
+                    }) => {
+                        // identifiers are case insensitive, keep the declared spelling
+                        *id = name;
+                        *scope_level = level;
+                        return Ok(Some(kind));
+                    }
+                    Some(Symbol::Const { value, kind, .. }) => (value, kind),
+                    Some(Symbol::Function { params, .. }) if params.is_empty() => {
+                        // a function without parameters is called by its name
+                        *node = AST::FunctionCall {
+                            id: id.clone(),
+                            ast_params: Vec::new(),
+                            params: Vec::new(),
+                            return_type: None,
+                            token: token.clone(),
+                            body: None,
+                            scope_level: 0,
+                        };
+                        return self.visit_node(node);
+                    }
+                    Some(Symbol::Function { .. }) => {
+                        return Err(Error::WRONG_ARGUMENTS_NUMBER(token.clone()))
+                    }
+                    Some(Symbol::BuiltIn { builtin, .. }) => {
+                        // built-in functions like eof can be called without arguments
+                        *node = AST::BuiltInCall {
+                            builtin,
+                            args: Vec::new(),
+                            arg_types: Vec::new(),
+                            token: token.clone(),
+                        };
+                        return self.visit_node(node);
+                    }
+                    Some(Symbol::Field { name, record, .. }) => {
+                        // a field of the record opened by WITH
+                        *node = AST::Field {
+                            record: Box::new(AST::Var {
+                                id: record,
+                                token: token.clone(),
+                                scope_level: 0,
+                            }),
+                            field: name,
+                            token: token.clone(),
+                        };
+                        return self.visit_node(node);
+                    }
+                    Some(_) => return Err(Error::TYPE_MISMATCH(token.clone())),
+                    None => return Err(Error::ID_NOT_FOUND(token.clone())),
+                };
+                *node = literal(&value, &kind);
+                return Ok(Some(kind));
+            }
+            AST::Index {
+                array,
+                index,
+                token,
+            } => {
+                let (index_type, element) = match self.visit_expr(array, token)? {
+                    VarType::ARRAY { index, element } => (*index, *element),
+                    VarType::STRING => (VarType::INTEGER, VarType::CHAR),
+                    _ => return Err(Error::TYPE_MISMATCH(token.clone())),
+                };
+                if !is_variable(array) {
+                    return Err(Error::VARIABLE_EXPECTED(token.clone()));
+                }
+                if self.visit_expr(index, token)?.base() != index_type.base() {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                // an index known at compile time is checked right away,
+                // the length of a string is known only at run time
+                if let (Some(value), Some((low, high))) =
+                    (constant_value(index), index_type.bounds())
+                {
+                    if value.as_ordinal() < low || value.as_ordinal() > high {
+                        return Err(Error::INDEX_OUT_OF_RANGE(token.clone(), value));
+                    }
                 }
+                return Ok(Some(element));
+            }
+            AST::Field {
+                record,
+                field,
+                token,
+            } => {
+                let record_type = self.visit_expr(record, token)?;
+                if !matches!(record_type, VarType::RECORD { .. }) {
+                    return Err(Error::TYPE_MISMATCH(token.clone()));
+                }
+                if !is_variable(record) {
+                    return Err(Error::VARIABLE_EXPECTED(token.clone()));
+                }
+                return match record_type.field(field) {
+                    Some((name, kind)) => {
+                        // keep the declared spelling of the field
+                        *field = name;
+                        Ok(Some(kind))
+                    }
+                    None => Err(Error::ID_NOT_FOUND(token.clone())),
+                };
+            }
+            AST::Deref { pointer, token } => {
+                let pointer = self.visit_expr(pointer, token)?;
+                return self.pointer_target(&pointer, token).map(Some);
+            }
+            AST::Nil => return Ok(Some(VarType::NIL)),
+            AST::Set { elements, token } => {
+                // all the elements are of the same ordinal type
+                let mut element: Option<VarType> = None;
+                for (low, high) in elements {
+                    for node in std::iter::once(low).chain(high) {
+                        let var_type = self.visit_expr(node, token)?.base();
+                        if !var_type.is_ordinal()
+                            || element.as_ref().is_some_and(|element| *element != var_type)
+                        {
+                            return Err(Error::TYPE_MISMATCH(token.clone()));
+                        }
+                        element = Some(var_type);
+                    }
+                }
+                return Ok(Some(VarType::SET {
+                    element: element.map(Box::new),
+                }));
+            }
+            AST::With {
+                record,
+                alias,
+                body,
+                token,
+            } => {
+                let kind = self.visit_expr(record, token)?;
+                let fields = match &kind {
+                    VarType::RECORD { fields, .. } => fields.clone(),
+                    _ => return Err(Error::TYPE_MISMATCH(token.clone())),
+                };
+                if !is_variable(record) {
+                    return Err(Error::VARIABLE_EXPECTED(token.clone()));
+                }
+                let read_only = self.check_variable(record, token).is_err();
+                self.with_depth += 1;
+                *alias = format!("$with{}", self.with_depth);
+
+                self.log("ENTER scope: with".to_string());
+                let current_scope_level = self.current_scope.scope_level();
+                let prev_scope = std::mem::replace(
+                    &mut self.current_scope,
+                    ScopedSymbolTable::empty(
+                        "with".to_string(),
+                        current_scope_level,
+                        self.debug_scope,
+                    ),
+                );
+                self.current_scope.set_enclosing_scope(prev_scope);
+                self.current_scope.insert(Symbol::Var {
+                    name: alias.clone(),
+                    kind,
+                    read_only,
+                    scope_level: 0,
+                });
+                for (name, kind) in fields {
+                    self.current_scope.insert(Symbol::Field {
+                        name,
+                        kind,
+                        record: alias.clone(),
+                        scope_level: 0,
+                    });
+                }
+
+                let res = self.nested_statements(std::slice::from_mut(body));
+
+                self.log(format!("{}", self.current_scope));
+                self.current_scope = self.current_scope.enclosing_scope();
+                self.log("LEAVE scope: with".to_string());
+                self.with_depth -= 1;
+                res?;
             }
             AST::ProcedureCall {
                 ast_params,
                 params,
-                ref mut body,
+                body,
                 scope_level,
                 id,
                 token,
-            } => {
-                for param in ast_params {
-                    self.visit_node(param)?;
+            } => match self.current_scope.lookup(id) {
+                Some(Symbol::Procedure {
+                    params: formal_params,
+                    body: routine,
+                    scope_level: level,
+                    ..
+                }) => {
+                    self.check_arguments(ast_params, &formal_params, token)?;
+                    *params = formal_params;
+                    *body = Some(routine);
+                    *scope_level = level;
+                }
+                Some(Symbol::BuiltIn { builtin, .. }) => {
+                    *node = AST::BuiltInCall {
+                        builtin,
+                        args: std::mem::take(ast_params),
+                        arg_types: Vec::new(),
+                        token: token.clone(),
+                    };
+                    self.visit_node(node)?;
                 }
-                if let Some(s) = self.current_scope.lookup(id) {
-                    *params = match s.clone() {
-                        Symbol::Procedure { params, .. } => params,
-                        _ => panic!("impossible"),
+                Some(Symbol::Function { .. }) => {
+                    // a function called as a statement, the result is discarded
+                    *node = AST::FunctionCall {
+                        id: id.clone(),
+                        ast_params: std::mem::take(ast_params),
+                        params: Vec::new(),
+                        return_type: None,
+                        token: token.clone(),
+                        body: None,
+                        scope_level: 0,
                     };
-                    let block = match s.clone() {
-                        Symbol::Procedure { block, .. } => block,
-                        _ => panic!("impossible"),
+                    self.visit_node(node)?;
+                }
+                Some(_) => return Err(Error::TYPE_MISMATCH(token.clone())),
+                None => return Err(Error::ID_NOT_FOUND(token.clone())),
+            },
+            AST::FunctionCall {
+                ast_params,
+                params,
+                return_type: result_type,
+                body,
+                scope_level,
+                id,
+                token,
+            } => match self.current_scope.lookup(id) {
+                Some(Symbol::Function {
+                    params: formal_params,
+                    return_type,
+                    body: routine,
+                    scope_level: level,
+                    ..
+                }) => {
+                    self.check_arguments(ast_params, &formal_params, token)?;
+                    *params = formal_params;
+                    *result_type = Some(return_type.clone());
+                    *body = Some(routine);
+                    *scope_level = level;
+                    return Ok(Some(return_type));
+                }
+                Some(Symbol::BuiltIn { builtin, .. }) => {
+                    *node = AST::BuiltInCall {
+                        builtin,
+                        args: std::mem::take(ast_params),
+                        arg_types: Vec::new(),
+                        token: token.clone(),
                     };
-                    *body = Some(Box::new(block));
-                    *scope_level = s.scope_level();
-                } else {
-                    return Err(Error::ID_NOT_FOUND(token.clone()));
+                    return self.visit_node(node);
                 }
-            }
+                Some(_) => return Err(Error::TYPE_MISMATCH(token.clone())),
+                None => return Err(Error::ID_NOT_FOUND(token.clone())),
+            },
         }
-        Ok(())
+        Ok(None)
     }
 }
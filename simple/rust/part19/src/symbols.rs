@@ -1,46 +1,80 @@
-use crate::ast::{Param, AST};
+use crate::ast::{Param, RoutineBody};
+use crate::builtins::BuiltIn;
+use crate::calc::CalcResult;
 use crate::var_type::VarType;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub enum Symbol {
-    BuiltIn {
+    Type {
         name: String,
+        kind: VarType,
         scope_level: usize,
     },
     Var {
         name: String,
         kind: VarType,
+        // const parameters can not be modified
+        read_only: bool,
+        scope_level: usize,
+    },
+    Const {
+        name: String,
+        value: CalcResult,
+        kind: VarType,
         scope_level: usize,
     },
+    // a field of the record opened by a WITH statement
+    Field {
+        name: String,
+        kind: VarType,
+        record: String,
+        scope_level: usize,
+    },
+    // a routine declared forward gets its block later
     Procedure {
         name: String,
         params: Vec<Param>,
-        block: AST,
+        body: RoutineBody,
+        forward: bool,
+        scope_level: usize,
+    },
+    Function {
+        name: String,
+        params: Vec<Param>,
+        return_type: VarType,
+        body: RoutineBody,
+        forward: bool,
+        scope_level: usize,
+    },
+    BuiltIn {
+        name: String,
+        builtin: BuiltIn,
+        scope_level: usize,
+    },
+    Label {
+        name: String,
         scope_level: usize,
     },
 }
 
 impl Symbol {
-    fn name(&self) -> String {
+    pub fn name(&self) -> String {
         match self {
-            Symbol::BuiltIn { name, .. } => name.clone(),
+            Symbol::Type { name, .. } => name.clone(),
             Symbol::Var { name, .. } => name.clone(),
+            Symbol::Const { name, .. } => name.clone(),
+            Symbol::Field { name, .. } => name.clone(),
             Symbol::Procedure { name, .. } => name.clone(),
-        }
-    }
-
-    pub fn scope_level(&self) -> usize {
-        match *self {
-            Symbol::BuiltIn { scope_level, .. } => scope_level,
-            Symbol::Var { scope_level, .. } => scope_level,
-            Symbol::Procedure { scope_level, .. } => scope_level,
+            Symbol::Function { name, .. } => name.clone(),
+            Symbol::BuiltIn { name, .. } => name.clone(),
+            Symbol::Label { name, .. } => name.clone(),
         }
     }
 
     fn set_scope_level(&mut self, level: usize) {
         match self {
-            Symbol::BuiltIn {
+            Symbol::Type {
                 ref mut scope_level,
                 ..
             } => *scope_level = level,
@@ -48,27 +82,71 @@ impl Symbol {
                 ref mut scope_level,
                 ..
             } => *scope_level = level,
+            Symbol::Const {
+                ref mut scope_level,
+                ..
+            } => *scope_level = level,
+            Symbol::Field {
+                ref mut scope_level,
+                ..
+            } => *scope_level = level,
             Symbol::Procedure {
                 ref mut scope_level,
                 ..
             } => *scope_level = level,
+            Symbol::Function {
+                ref mut scope_level,
+                ..
+            } => *scope_level = level,
+            Symbol::BuiltIn {
+                ref mut scope_level,
+                ..
+            } => *scope_level = level,
+            Symbol::Label {
+                ref mut scope_level,
+                ..
+            } => *scope_level = level,
         };
     }
 }
 impl std::fmt::Display for Symbol {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         let line = match self {
-            Symbol::BuiltIn { name, scope_level } => {
-                format!("<BuiltIn(name='{}', scope_level={})>", name, scope_level)
-            }
+            Symbol::Type {
+                name,
+                kind,
+                scope_level,
+            } => format!(
+                "<Type(name='{}', type='{}', scope_level={})>",
+                name, kind, scope_level
+            ),
             Symbol::Var {
                 name,
                 kind,
                 scope_level,
+                ..
             } => format!(
                 "<Var(name='{}', type='{}', scope_level={})>",
                 name, kind, scope_level
             ),
+            Symbol::Const {
+                name,
+                value,
+                scope_level,
+                ..
+            } => format!(
+                "<Const(name='{}', value='{}', scope_level={})>",
+                name, value, scope_level
+            ),
+            Symbol::Field {
+                name,
+                kind,
+                record,
+                scope_level,
+            } => format!(
+                "<Field(name='{}', type='{}', record='{}', scope_level={})>",
+                name, kind, record, scope_level
+            ),
             Symbol::Procedure {
                 name,
                 params,
@@ -86,6 +164,31 @@ impl std::fmt::Display for Symbol {
                     scope_level
                 )
             }
+            Symbol::Function {
+                name,
+                params,
+                return_type,
+                scope_level,
+                ..
+            } => {
+                format!(
+                    "<Function(name='{}', parameters=[{}], return_type='{}', scope_level={})>",
+                    name,
+                    params
+                        .iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    return_type,
+                    scope_level
+                )
+            }
+            Symbol::BuiltIn {
+                name, scope_level, ..
+            } => format!("<BuiltIn(name='{}', scope_level={})>", name, scope_level),
+            Symbol::Label { name, scope_level } => {
+                format!("<Label(name='{}', scope_level={})>", name, scope_level)
+            }
         };
         write!(fmt, "{}", line)
     }
@@ -126,14 +229,14 @@ impl std::fmt::Display for ScopedSymbolTable {
         .for_each(|line| output.push_str(&line));
         output.push('\n');
         let header = "Scope (Scoped symbol table) contents";
-        output.push_str(&header);
+        output.push_str(header);
         output.push('\n');
         output.push_str(&vec!['-'; header.len()].iter().collect::<String>());
         output.push('\n');
         let mut key_values: Vec<String> = self
             .table
-            .iter()
-            .map(|(k, v)| format!("{} = {}", k, v))
+            .values()
+            .map(|v| format!("{} = {}", v.name(), v))
             .collect();
         key_values.sort();
         output = key_values.iter().fold(output, |mut output, line| {
@@ -147,17 +250,38 @@ impl std::fmt::Display for ScopedSymbolTable {
 
 impl ScopedSymbolTable {
     pub fn new(scope_name: String, scope_level: usize, debug_scope: bool) -> ScopedSymbolTable {
-        let mut new = ScopedSymbolTable {
-            table: HashMap::new(),
-            scope_level,
-            scope_name,
-            enclosing_scope: None,
-            debug_scope,
-        };
+        let mut new = ScopedSymbolTable::empty(scope_name, scope_level, debug_scope);
         if scope_level == 1 {
-            vec![VarType::INTEGER, VarType::REAL].iter().for_each(|t| {
-                new.insert(Symbol::BuiltIn {
+            [
+                VarType::INTEGER,
+                VarType::REAL,
+                VarType::BOOLEAN,
+                VarType::CHAR,
+                VarType::STRING,
+                VarType::TEXT,
+            ]
+            .iter()
+            .for_each(|t| {
+                new.insert(Symbol::Type {
                     name: t.name(),
+                    kind: t.clone(),
+                    scope_level,
+                });
+            });
+            [("TRUE", true), ("FALSE", false)]
+                .iter()
+                .for_each(|(name, value)| {
+                    new.insert(Symbol::Const {
+                        name: name.to_string(),
+                        value: CalcResult::BOOLEAN(*value),
+                        kind: VarType::BOOLEAN,
+                        scope_level,
+                    });
+                });
+            BuiltIn::ALL.iter().for_each(|builtin| {
+                new.insert(Symbol::BuiltIn {
+                    name: builtin.name(),
+                    builtin: *builtin,
                     scope_level,
                 });
             });
@@ -165,8 +289,20 @@ impl ScopedSymbolTable {
         new
     }
 
+    // a scope without the builtin symbols, WITH statements open such scopes
+    // on the level of the routine they are in
+    pub fn empty(scope_name: String, scope_level: usize, debug_scope: bool) -> ScopedSymbolTable {
+        ScopedSymbolTable {
+            table: HashMap::new(),
+            scope_level,
+            scope_name,
+            enclosing_scope: None,
+            debug_scope,
+        }
+    }
+
     pub fn enclosing_scope(&mut self) -> ScopedSymbolTable {
-        let enclosing_scope = std::mem::replace(&mut self.enclosing_scope, None);
+        let enclosing_scope = self.enclosing_scope.take();
         *enclosing_scope.unwrap()
     }
 
@@ -178,11 +314,25 @@ impl ScopedSymbolTable {
         self.scope_level
     }
 
+    // checks whether this scope is the body of the given routine or is nested in it
+    pub fn is_within(&self, name: &str, scope_level: usize) -> bool {
+        if self.scope_level == scope_level && self.scope_name.eq_ignore_ascii_case(name) {
+            return true;
+        }
+        match &self.enclosing_scope {
+            Some(enclosing_scope) if self.scope_level >= scope_level => {
+                enclosing_scope.is_within(name, scope_level)
+            }
+            _ => false,
+        }
+    }
+
     pub fn insert(&mut self, symbol: Symbol) {
         self.log(format!("Insert: {}", symbol.name()));
         let mut symbol = symbol;
         symbol.set_scope_level(self.scope_level());
-        self.table.insert(symbol.name(), symbol);
+        // pascal identifiers are case insensitive
+        self.table.insert(symbol.name().to_uppercase(), symbol);
     }
 
     pub fn lookup(&self, name: &str) -> Option<Symbol> {
@@ -190,7 +340,7 @@ impl ScopedSymbolTable {
             "Lookup: {} (Scope name: {})",
             name, self.scope_name
         ));
-        if let Some(symbol) = self.table.get(name) {
+        if let Some(symbol) = self.table.get(&name.to_uppercase()) {
             Some(symbol.clone())
         } else if self.enclosing_scope.as_ref().unwrap().scope_level != 0 {
             self.enclosing_scope.as_ref().unwrap().lookup(name)
@@ -199,16 +349,37 @@ impl ScopedSymbolTable {
         }
     }
 
+    // finds a symbol declared on the given level or on an enclosing one,
+    // symbols of deeper scopes which hide it are skipped
+    pub fn lookup_at(&self, name: &str, scope_level: usize) -> Option<Symbol> {
+        if self.scope_level <= scope_level {
+            return self.lookup(name);
+        }
+        self.enclosing_scope.as_ref()?.lookup_at(name, scope_level)
+    }
+
+    // finds a type declared on the given level or on an enclosing one,
+    // symbols of deeper scopes which hide the type are skipped
+    pub fn lookup_type(&self, name: &str, scope_level: usize) -> Option<VarType> {
+        if self.scope_level <= scope_level {
+            if let Some(Symbol::Type { kind, .. }) = self.table.get(&name.to_uppercase()) {
+                return Some(kind.clone());
+            }
+        }
+        match &self.enclosing_scope {
+            Some(enclosing_scope) if enclosing_scope.scope_level != 0 => {
+                enclosing_scope.lookup_type(name, scope_level)
+            }
+            _ => None,
+        }
+    }
+
     pub fn lookup_current_only(&self, name: &str) -> Option<Symbol> {
         self.log(format!(
             "Lookup: {} (Scope name: {})",
             name, self.scope_name
         ));
-        if let Some(symbol) = self.table.get(name) {
-            Some(symbol.clone())
-        } else {
-            None
-        }
+        self.table.get(&name.to_uppercase()).cloned()
     }
 
     fn log(&self, line: String) {
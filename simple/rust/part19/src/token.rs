@@ -1,11 +1,13 @@
-use crate::var_type::VarType;
-
 #[derive(Debug, Clone, PartialEq)]
 pub enum Kind {
     #[allow(non_camel_case_types)]
     INTEGER_CONST(i32),
     #[allow(non_camel_case_types)]
-    REAL_CONST(f32),
+    REAL_CONST(f64),
+    #[allow(non_camel_case_types)]
+    CHAR_CONST(char),
+    #[allow(non_camel_case_types)]
+    STRING_CONST(String),
     PLUS,
     MINUS,
     MUL,
@@ -13,21 +15,63 @@ pub enum Kind {
     COLON,
     LPAREN,
     RPAREN,
+    LBRACKET,
+    RBRACKET,
+    CARET,
     ID(String),
     ASSIGN,
     BEGIN,
     PROGRAM,
     VAR,
+    CONST,
     #[allow(non_camel_case_types)]
     INTEGER_DIV,
     #[allow(non_camel_case_types)]
     FLOAT_DIV,
-    TYPE(VarType),
+    MOD,
+    AND,
+    OR,
+    NOT,
+    XOR,
+    SHL,
+    SHR,
+    TYPE,
     END,
     SEMI,
     DOT,
     EOF,
     PROCEDURE,
+    FUNCTION,
+    IF,
+    THEN,
+    ELSE,
+    WHILE,
+    DO,
+    REPEAT,
+    UNTIL,
+    FOR,
+    TO,
+    DOWNTO,
+    CASE,
+    OF,
+    ARRAY,
+    RECORD,
+    WITH,
+    NIL,
+    SET,
+    IN,
+    LABEL,
+    GOTO,
+    RANGE,
+    EQUAL,
+    #[allow(non_camel_case_types)]
+    NOT_EQUAL,
+    LESS,
+    #[allow(non_camel_case_types)]
+    LESS_EQUAL,
+    GREATER,
+    #[allow(non_camel_case_types)]
+    GREATER_EQUAL,
 }
 
 impl std::fmt::Display for Kind {
@@ -42,6 +86,8 @@ pub struct Token {
     pub kind: Kind,
     pub lineno: usize,
     pub column: usize,
+    // whether I/O errors stop the program, switched by {$I+} and {$I-}
+    pub io_checks: bool,
 }
 
 impl std::fmt::Display for Token {
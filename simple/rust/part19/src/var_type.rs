@@ -2,11 +2,67 @@
 pub enum VarType {
     INTEGER,
     REAL,
+    BOOLEAN,
+    CHAR,
+    STRING,
+    SUBRANGE {
+        base: Box<VarType>,
+        low: i32,
+        high: i32,
+    },
+    ENUM {
+        name: String,
+        values: Vec<String>,
+    },
+    // several dimensions are arrays of arrays
+    ARRAY {
+        index: Box<VarType>,
+        element: Box<VarType>,
+    },
+    RECORD {
+        name: String,
+        fields: Vec<(String, VarType)>,
+    },
+    // the pointed type is referred to by its name and the level of its scope,
+    // so that records can point to themselves
+    POINTER {
+        target: String,
+        scope_level: usize,
+    },
+    // the type of nil, which is compatible with every pointer
+    NIL,
+    // the empty set constructor has no element type and is compatible with every set
+    SET {
+        element: Option<Box<VarType>>,
+    },
+    // text files can only be passed by reference
+    TEXT,
 }
 
 impl std::fmt::Display for VarType {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "{:?}", self)
+        match self {
+            VarType::SUBRANGE { base, low, high } => match base.as_ref() {
+                VarType::ENUM { values, .. } => {
+                    write!(fmt, "{}..{}", values[*low as usize], values[*high as usize])
+                }
+                VarType::CHAR => write!(
+                    fmt,
+                    "'{}'..'{}'",
+                    char::from_u32(*low as u32).unwrap_or_default(),
+                    char::from_u32(*high as u32).unwrap_or_default()
+                ),
+                _ => write!(fmt, "{}..{}", low, high),
+            },
+            VarType::ENUM { name, .. } | VarType::RECORD { name, .. } => write!(fmt, "{}", name),
+            VarType::ARRAY { index, element } => write!(fmt, "array[{}] of {}", index, element),
+            VarType::POINTER { target, .. } => write!(fmt, "^{}", target),
+            VarType::SET {
+                element: Some(element),
+            } => write!(fmt, "set of {}", element),
+            VarType::SET { element: None } => write!(fmt, "[]"),
+            any => write!(fmt, "{:?}", any),
+        }
     }
 }
 
@@ -14,4 +70,88 @@ impl VarType {
     pub fn name(&self) -> String {
         format!("{}", self)
     }
+
+    // the type a subrange is taken from, other types are their own base
+    pub fn base(&self) -> VarType {
+        match self {
+            VarType::SUBRANGE { base, .. } => *base.clone(),
+            any => any.clone(),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self.base(), VarType::INTEGER | VarType::REAL)
+    }
+
+    pub fn is_set(&self) -> bool {
+        matches!(self, VarType::SET { .. })
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self, VarType::POINTER { .. } | VarType::NIL)
+    }
+
+    pub fn is_ordinal(&self) -> bool {
+        matches!(
+            self.base(),
+            VarType::INTEGER | VarType::BOOLEAN | VarType::CHAR | VarType::ENUM { .. }
+        )
+    }
+
+    // the first and the last ordinal numbers of a type which can index an array
+    pub fn bounds(&self) -> Option<(i32, i32)> {
+        match self {
+            VarType::SUBRANGE { low, high, .. } => Some((*low, *high)),
+            VarType::ENUM { values, .. } => Some((0, values.len() as i32 - 1)),
+            VarType::BOOLEAN => Some((0, 1)),
+            VarType::CHAR => Some((0, 255)),
+            _ => None,
+        }
+    }
+
+    // checks an ordinal value against the bounds, types without them contain all values
+    pub fn contains(&self, ordinal: i32) -> bool {
+        self.bounds()
+            .is_none_or(|(low, high)| (low..=high).contains(&ordinal))
+    }
+
+    // finds the field of a record, field names are case insensitive
+    pub fn field(&self, id: &str) -> Option<(String, VarType)> {
+        match self {
+            VarType::RECORD { fields, .. } => fields
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(id))
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    // checks whether a value of type `other` can be assigned to a variable of this type
+    pub fn accepts(&self, other: &VarType) -> bool {
+        let (base, other) = (self.base(), other.base());
+        match (&base, &other) {
+            (
+                VarType::POINTER {
+                    target,
+                    scope_level,
+                },
+                VarType::POINTER {
+                    target: other,
+                    scope_level: other_level,
+                },
+            ) => target.eq_ignore_ascii_case(other) && scope_level == other_level,
+            (VarType::POINTER { .. }, VarType::NIL) => true,
+            (VarType::TEXT, _) => false,
+            (VarType::SET { element }, VarType::SET { element: other }) => match (element, other) {
+                (Some(element), Some(other)) => element.base() == other.base(),
+                (_, None) => true,
+                (None, Some(_)) => false,
+            },
+            _ => {
+                base == other
+                    || (base == VarType::REAL && other == VarType::INTEGER)
+                    || (base == VarType::STRING && other == VarType::CHAR)
+            }
+        }
+    }
 }
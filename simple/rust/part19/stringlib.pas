@@ -0,0 +1,44 @@
+program StringLib;
+
+const
+   Greeting = 'Hello, World';
+   Size = length(Greeting);
+
+var
+   s, t : string;
+   i, code : integer;
+   x : real;
+
+begin
+   writeln('length: ', length(Greeting), ' ', Size, ' ', length(''));
+   writeln('copy: ', copy(Greeting, 8, 5), '|', copy(Greeting, 10, 100), '|', copy(Greeting, 20, 1), '|');
+   writeln('pos: ', pos('World', Greeting), ' ', pos('o', Greeting), ' ', pos('x', Greeting));
+   writeln('concat: ', concat('a', 'b', 'c'), ' ', concat(Greeting));
+   s := 'Pascal';
+   insert('Turbo ', s, 1);
+   writeln('insert: ', s);
+   insert('!', s, 100);
+   writeln('insert: ', s);
+   delete(s, 1, 6);
+   writeln('delete: ', s);
+   delete(s, 7, 10);
+   writeln('delete: ', s);
+   writeln('upcase: ', upcase('q'), ' ', upcase(s));
+   t := IntToStr(42) + '/' + IntToStr(-7);
+   writeln('IntToStr: ', t);
+   writeln('StrToInt: ', StrToInt('123') + 1);
+   val('  256', i, code);
+   writeln('val: ', i, ' ', code);
+   val('12x4', i, code);
+   writeln('val: ', i, ' ', code);
+   val('-1.5e2', x, code);
+   writeln('val: ', x:0:1, ' ', code);
+   str(3.14159:8:3, s);
+   writeln('str: [', s, ']');
+   str(i:5, s);
+   writeln('str: [', s, ']');
+   s := Greeting;
+   for i := length(s) downto 1 do
+      write(s[i]);
+   writeln
+end.
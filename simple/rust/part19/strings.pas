@@ -0,0 +1,53 @@
+program Strings;
+
+const
+   Greeting = 'Hello';
+   Space = ' ';
+
+type
+   TLetter = 'a'..'z';
+
+var
+   name, message, quoted : string;
+   c, first : char;
+   letter : TLetter;
+   counts : array['a'..'e'] of integer;
+   vowels, i : integer;
+   ordered, same : boolean;
+   x : real;
+
+procedure Capitalize(var s : string);
+begin
+   case s[1] of
+      'a'..'z': s[1] := 'W'
+   else
+      s := s
+   end
+end;
+
+begin
+   name := 'world';
+   Capitalize(name);
+   message := Greeting + ',' + Space + name + '!';
+   quoted := 'It''s';
+   first := message[1];
+   c := 'a';
+   name := c;
+   ordered := 'abc' < 'abd';
+   same := 'a' = name;
+
+   vowels := 0;
+   for letter := 'a' to 'e' do
+   begin
+      counts[letter] := 0;
+      case letter of
+         'a', 'e': vowels := vowels + 1
+      else
+         vowels := vowels
+      end
+   end;
+   for c := 'a' to 'c' do
+      counts[c] := counts[c] + 1;
+   i := 3;
+   x := i
+end.
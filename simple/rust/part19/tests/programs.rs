@@ -0,0 +1,219 @@
+use std::process::Command;
+
+// the output of a program run by the interpreter
+struct Run {
+    stdout: String,
+    stderr: String,
+    code: i32,
+}
+
+// runs the source of a pascal program, the name keeps the source files of the tests apart
+fn run(name: &str, source: &str) -> Run {
+    let path = std::env::temp_dir().join(format!("part19_{}.pas", name));
+    std::fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_part19"))
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    Run {
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+        code: output.status.code().unwrap(),
+    }
+}
+
+#[test]
+fn array_result_filled_by_elements() {
+    let run = run(
+        "array_result",
+        "program ArrayResult;
+         type
+            Triple = array[1..3] of integer;
+         var
+            t : Triple;
+         function Squares : Triple;
+         var
+            i : integer;
+         begin
+            for i := 1 to 3 do
+               Squares[i] := i * i
+         end;
+         begin
+            t := Squares;
+            writeln(t[1], ' ', t[2], ' ', t[3])
+         end.",
+    );
+    assert_eq!(run.stdout, "1 4 9\n");
+    assert_eq!(run.stderr, "");
+    assert_eq!(run.code, 0);
+}
+
+#[test]
+fn record_result_filled_by_fields() {
+    let run = run(
+        "record_result",
+        "program RecordResult;
+         type
+            Point = record
+               x, y : integer
+            end;
+         var
+            p : Point;
+         function MakePoint(x, y : integer) : Point;
+         begin
+            MakePoint.x := x;
+            Result.y := y
+         end;
+         begin
+            p := MakePoint(4, 5);
+            writeln(p.x, ' ', p.y)
+         end.",
+    );
+    assert_eq!(run.stdout, "4 5\n");
+    assert_eq!(run.stderr, "");
+    assert_eq!(run.code, 0);
+}
+
+#[test]
+fn out_as_a_name_and_a_parameter_mode() {
+    let run = run(
+        "out_name",
+        "program OutName;
+         var
+            out : integer;
+         procedure Double(out : integer; out r : integer);
+         begin
+            r := out * 2
+         end;
+         begin
+            Double(21, out);
+            writeln(out)
+         end.",
+    );
+    assert_eq!(run.stdout, "42\n");
+    assert_eq!(run.stderr, "");
+    assert_eq!(run.code, 0);
+}
+
+#[test]
+fn parameter_named_result() {
+    let run = run(
+        "result_parameter",
+        "program ResultParameter;
+         function Twice(result : integer) : integer;
+         begin
+            Twice := result * 2
+         end;
+         begin
+            writeln(Twice(2))
+         end.",
+    );
+    assert_eq!(run.stdout, "");
+    assert!(run.stderr.contains("Duplicate id found"));
+    assert_ne!(run.code, 0);
+}
+
+#[test]
+fn for_bound_out_of_subrange() {
+    let run = run(
+        "for_subrange",
+        "program ForSubrange;
+         var
+            d : 0..9;
+         begin
+            for d := 0 to 20 do
+               write(d)
+         end.",
+    );
+    assert_eq!(run.stdout, "");
+    assert!(run.stderr.contains("Range check error"));
+    assert_ne!(run.code, 0);
+}
+
+#[test]
+fn read_out_of_subrange() {
+    let path = std::env::temp_dir().join("part19_read_subrange.txt");
+    std::fs::write(&path, "12\n").unwrap();
+    let run = run(
+        "read_subrange",
+        &format!(
+            "program ReadSubrange;
+             var
+                f : text;
+                d : 0..9;
+             begin
+                assign(f, '{}');
+                reset(f);
+                read(f, d);
+                writeln(d)
+             end.",
+            path.display()
+        ),
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(run.stdout, "");
+    assert!(run.stderr.contains("Range check error"));
+    assert_ne!(run.code, 0);
+}
+
+#[test]
+fn val_out_of_subrange() {
+    let run = run(
+        "val_subrange",
+        "program ValSubrange;
+         var
+            d : 0..9;
+            code : integer;
+         begin
+            val('12', d, code);
+            writeln(d)
+         end.",
+    );
+    assert_eq!(run.stdout, "");
+    assert!(run.stderr.contains("Range check error"));
+    assert_ne!(run.code, 0);
+}
+
+#[test]
+fn deep_linear_recursion() {
+    let run = run(
+        "deep_recursion",
+        "program DeepRecursion;
+         function Depth(n : integer) : integer;
+         begin
+            if n = 0 then
+               Depth := 0
+            else
+               Depth := Depth(n - 1) + 1
+         end;
+         begin
+            writeln(Depth(10000))
+         end.",
+    );
+    assert_eq!(run.stdout, "10000\n");
+    assert_eq!(run.stderr, "");
+    assert_eq!(run.code, 0);
+}
+
+#[test]
+fn otherwise_as_a_name_and_a_case_part() {
+    let run = run(
+        "otherwise_name",
+        "program OtherwiseName;
+         var
+            otherwise : integer;
+         begin
+            otherwise := 3;
+            case otherwise of
+               1 : writeln('one');
+               2 : writeln('two');
+            otherwise
+               writeln('other ', otherwise)
+            end
+         end.",
+    );
+    assert_eq!(run.stdout, "other 3\n");
+    assert_eq!(run.stderr, "");
+    assert_eq!(run.code, 0);
+}
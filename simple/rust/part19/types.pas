@@ -0,0 +1,41 @@
+program Types;
+
+const
+   Last = 9;
+
+type
+   TCount = integer;
+   TDigit = 0..Last;
+   TColor = (Red, Green, Blue);
+   TWarm = Red..Green;
+   TPaint = TColor;
+
+var
+   count : TCount;
+   digit : TDigit;
+   color, best : TPaint;
+   warm : TWarm;
+   day : (Mon, Tue, Wed);
+   i : integer;
+
+function Next(c : TColor) : TColor;
+begin
+   case c of
+      Red: Next := Green;
+      Green: Next := Blue;
+      Blue: Next := Red
+   end
+end;
+
+begin
+   count := 0;
+   for color := Red to Blue do
+      count := count + 1;
+   digit := count * 3;
+   color := Next(Blue);
+   best := Blue;
+   if color < best then
+      warm := Next(color);
+   day := Wed;
+   i := digit + 1
+end.
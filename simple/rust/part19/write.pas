@@ -0,0 +1,31 @@
+program Write;
+
+type
+   TColor = (Red, Green, Blue);
+
+var
+   i : integer;
+   r : real;
+   name : string;
+   c : TColor;
+
+begin
+   writeln('Hello, world!');
+   name := 'Pascal';
+   write('Hello, ', name);
+   writeln('!');
+   writeln;
+
+   r := 3.14159;
+   writeln(r);
+   writeln(-r);
+   writeln(r:12);
+   writeln(r:0:2, ' ', r:8:3, '|');
+   writeln(1 / 3:10:4);
+
+   for i := 1 to 3 do
+      writeln(i:3, i * i:5, 'x':2);
+   c := Green;
+   writeln('color = ', c, ', ok = ', i = 3, ', char = ', 'z');
+   WriteLn('[', name:10, ']')
+end.